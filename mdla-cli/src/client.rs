@@ -0,0 +1,56 @@
+use std::error::Error;
+
+use mdla_lib::model::{GuessBody, GuessResponseOrError, HintsQuery, HintsResponseOrError};
+use serde::de::DeserializeOwned;
+
+/// Client of the API of a mdla server.
+pub struct Client {
+    agent: ureq::Agent,
+    api: String,
+}
+
+impl Client {
+    pub fn new(server: &str) -> Self {
+        Self {
+            agent: ureq::agent(),
+            api: format!("{}/api/v1", server.trim_end_matches('/')),
+        }
+    }
+
+    /// Start a game of a puzzle, or of a challenge.
+    pub fn hints(&self, query: &HintsQuery) -> Result<HintsResponseOrError, Box<dyn Error>> {
+        let mut request = self
+            .agent
+            .get(&format!("{}/hints", self.api))
+            .query("hard", &query.hard.to_string());
+        if let Some(puzzle) = query.puzzle {
+            request = request.query("puzzle", &puzzle.to_string());
+        }
+        if let Some(length) = query.length {
+            request = request.query("length", &length.to_string());
+        }
+        if let Some(challenge) = &query.challenge {
+            request = request.query("challenge", challenge);
+        }
+        read(request.call())
+    }
+
+    pub fn guess(&self, body: &GuessBody) -> Result<GuessResponseOrError, Box<dyn Error>> {
+        read(
+            self.agent
+                .post(&format!("{}/guess", self.api))
+                .send_json(body),
+        )
+    }
+}
+
+/// Read the answer of the API, errors included: they come with a 4xx or 5xx status.
+fn read<T: DeserializeOwned>(
+    result: Result<ureq::Response, ureq::Error>,
+) -> Result<T, Box<dyn Error>> {
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(response.into_json()?)
+}
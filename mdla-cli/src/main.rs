@@ -0,0 +1,147 @@
+use std::{
+    env,
+    error::Error,
+    io::{self, BufRead, Write},
+    process,
+};
+
+use client::Client;
+use mdla_lib::{
+    error::ApiError,
+    messages::error_message,
+    model::{
+        AppError, GameOutcome, GuessBody, GuessResponseOrError, HintsQuery, HintsResponseOrError,
+    },
+    share::{game_label, share_text},
+};
+use structopt::StructOpt;
+
+mod client;
+mod render;
+
+#[derive(StructOpt, Debug)]
+#[structopt(about = "play mdla in the terminal !")]
+pub struct Cli {
+    /// Address of the mdla server
+    #[structopt(short = "s", long = "server", default_value = "http://localhost:8000")]
+    server: String,
+
+    /// Number of the puzzle of the archive to play, today's puzzle if none
+    #[structopt(short = "n", long = "puzzle")]
+    puzzle: Option<i64>,
+
+    /// Play the puzzle with words of this length, the classic puzzle if none
+    #[structopt(short = "l", long = "length")]
+    length: Option<usize>,
+
+    /// Play this challenge instead of a puzzle
+    #[structopt(short = "c", long = "challenge")]
+    challenge: Option<String>,
+
+    /// Hard mode: the letters found must be used in the next guesses
+    #[structopt(long = "hard")]
+    hard: bool,
+
+    /// Don't color the grid, as when the `NO_COLOR` environment variable is set
+    #[structopt(long = "no-color")]
+    no_color: bool,
+}
+
+fn main() {
+    if let Err(e) = play(Cli::from_args()) {
+        eprintln!("{e}");
+        process::exit(1);
+    }
+}
+
+/// Play a game, until it is over or the input ends.
+fn play(opt: Cli) -> Result<(), Box<dyn Error>> {
+    let colors = !opt.no_color && env::var_os("NO_COLOR").is_none();
+    let client = Client::new(&opt.server);
+
+    let query = HintsQuery {
+        puzzle: opt.puzzle,
+        length: opt.length,
+        challenge: opt.challenge.clone(),
+        hard: opt.hard,
+    };
+    let hints = match client.hints(&query)? {
+        HintsResponseOrError::Response(hints) => hints,
+        HintsResponseOrError::Error(ApiError::App(error)) => {
+            return Err(error_message(&error).into())
+        }
+        HintsResponseOrError::Error(error) => return Err(error.into()),
+    };
+
+    println!("MdlA {}", game_label(&hints));
+    println!(
+        "Mot de {} lettres commençant par {}",
+        hints.number_of_letters, hints.first_letter
+    );
+    println!();
+    println!(
+        "{}",
+        render::hint_row(hints.first_letter, hints.number_of_letters, colors)
+    );
+
+    let mut guesses = vec![];
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let guess = match lines.next() {
+            Some(line) => line?,
+            // End of the input, the player left the game
+            None => return Ok(()),
+        };
+        if guess.trim().is_empty() {
+            continue;
+        }
+
+        let body = GuessBody {
+            game_id: hints.game_id.clone(),
+            puzzle: opt.puzzle,
+            length: opt.length,
+            practice: false,
+            challenge: opt.challenge.clone(),
+            guess,
+        };
+        match client.guess(&body)? {
+            GuessResponseOrError::Response(response) => {
+                println!("{}", render::row(&response.validation_list, colors));
+                let outcome = response.outcome.clone();
+                guesses.push(response);
+                match outcome {
+                    GameOutcome::Ongoing { attempts_left } => {
+                        println!("Encore {attempts_left} essai(s)")
+                    }
+                    GameOutcome::Won => {
+                        println!("Bravo ! \\o/");
+                        break;
+                    }
+                    GameOutcome::GameOver { word } => {
+                        println!("Perdu... Le mot était {word}.");
+                        break;
+                    }
+                }
+            }
+            // The game can't go on
+            GuessResponseOrError::Error(ApiError::App(
+                error @ (AppError::GameNotFound(_) | AppError::GameFinished),
+            )) => return Err(error_message(&error).into()),
+            // The guess is refused, the player can try another one
+            GuessResponseOrError::Error(ApiError::App(error)) => {
+                println!("{}", error_message(&error))
+            }
+            GuessResponseOrError::Error(error) => return Err(error.into()),
+        }
+    }
+
+    println!();
+    println!("Partage ton score:");
+    println!();
+    for line in share_text(&hints, &guesses) {
+        println!("{line}");
+    }
+    Ok(())
+}
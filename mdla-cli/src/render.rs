@@ -0,0 +1,73 @@
+use mdla_lib::model::Validation;
+
+const RESET: &str = "\x1b[0m";
+/// White on red, as the letters at the right place in Motus
+const CORRECT: &str = "\x1b[1;97;41m";
+/// Black on yellow, as the letters at the wrong place
+const PRESENT: &str = "\x1b[1;30;43m";
+/// White on blue, as the letters not in the word
+const NOT_IN_WORD: &str = "\x1b[1;97;44m";
+
+/// A cell of the grid, colored with ANSI escape codes.
+///
+/// Without colors, the letters at the right place are in brackets and the ones at the wrong place in parentheses.
+pub fn cell(validation: &Validation, colors: bool) -> String {
+    let (style, letter, marks) = match validation {
+        Validation::Correct(letter) => (CORRECT, letter, ('[', ']')),
+        Validation::Present(letter) => (PRESENT, letter, ('(', ')')),
+        Validation::NotInWord(letter) => (NOT_IN_WORD, letter, (' ', ' ')),
+    };
+
+    if colors {
+        format!("{style} {letter} {RESET}")
+    } else {
+        format!("{}{letter}{}", marks.0, marks.1)
+    }
+}
+
+/// A line of the grid, for a guess.
+pub fn row(validations: &[Validation], colors: bool) -> String {
+    validations.iter().map(|v| cell(v, colors)).collect()
+}
+
+/// The first line of the grid, before any guess: the first letter of the word and a dot for each other letter.
+pub fn hint_row(first_letter: char, number_of_letters: usize, colors: bool) -> String {
+    let validations: Vec<Validation> = std::iter::once(first_letter)
+        .chain(std::iter::repeat('.'))
+        .take(number_of_letters)
+        .map(Validation::NotInWord)
+        .collect();
+    row(&validations, colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use mdla_lib::scoring::score;
+
+    use super::*;
+
+    #[test]
+    fn test_cell() {
+        assert_eq!(
+            cell(&Validation::Correct('A'), true),
+            "\x1b[1;97;41m A \x1b[0m"
+        );
+        assert_eq!(
+            cell(&Validation::Present('B'), true),
+            "\x1b[1;30;43m B \x1b[0m"
+        );
+        assert_eq!(cell(&Validation::Correct('A'), false), "[A]");
+        assert_eq!(cell(&Validation::Present('B'), false), "(B)");
+        assert_eq!(cell(&Validation::NotInWord('C'), false), " C ");
+    }
+
+    #[test]
+    fn test_row() {
+        assert_eq!(
+            row(&score("ABAISSE", "ACABITS"), false),
+            "[A] C [A](B)(I) T (S)"
+        );
+        assert_eq!(hint_row('A', 4, false), " A  .  .  . ");
+        assert_eq!(hint_row('A', 4, true).matches(RESET).count(), 4);
+    }
+}
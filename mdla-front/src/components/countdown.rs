@@ -0,0 +1,51 @@
+use gloo_timers::callback::Interval;
+use yew::prelude::*;
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct CountdownProperties {
+    /// Unix timestamp, in seconds, the countdown runs to
+    pub target: i64,
+}
+
+#[derive(Debug)]
+pub struct CountdownComponent {
+    // We need to keep the interval, it is cancelled when dropped
+    _interval: Interval,
+}
+
+impl Component for CountdownComponent {
+    type Message = ();
+    type Properties = CountdownProperties;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let link = ctx.link().clone();
+        let interval = Interval::new(1000, move || link.send_message(()));
+        Self {
+            _interval: interval,
+        }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let now = (js_sys::Date::now() / 1000.0) as i64;
+        let remaining = (ctx.props().target - now).max(0);
+
+        html! {
+            <p class="countdown">
+                {format!(
+                    "Prochain mot dans {:02}:{:02}:{:02}",
+                    remaining / 3600,
+                    remaining % 3600 / 60,
+                    remaining % 60
+                )}
+            </p>
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: ()) -> bool {
+        true
+    }
+}
@@ -14,6 +14,8 @@ pub struct GridInputComponent {
 #[derive(Debug, Properties, PartialEq)]
 pub struct GridInputProperties {
     pub width: usize,
+    /// Letter shown and locked in the first cell, if the guesses must start with it
+    pub first_letter: Option<char>,
     pub on_guessed_word_change: Callback<String>,
     pub on_validate: Callback<()>,
 }
@@ -30,17 +32,18 @@ impl Component for GridInputComponent {
     type Message = Msg;
     type Properties = GridInputProperties;
 
-    fn create(_ctx: &Context<Self>) -> Self {
+    fn create(ctx: &Context<Self>) -> Self {
+        let first_input_id = format!("input-cell-{}", first_input(ctx.props()));
         let body_on_click = Closure::wrap(Box::new(move || {
             info!("Click on body");
 
             if is_focus_on_input().is_none() {
-                focus_on_id("input-cell-0");
+                focus_on_id(&first_input_id);
             }
         }) as Box<dyn Fn()>);
 
         Self {
-            guessed_word: String::default(),
+            guessed_word: locked_word(ctx.props()),
             body_on_click,
         }
     }
@@ -49,7 +52,7 @@ impl Component for GridInputComponent {
         true
     }
 
-    fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
         info!("Rendered");
         if first_render {
             let body = window()
@@ -65,7 +68,10 @@ impl Component for GridInputComponent {
             )
             .expect("On click call back should work");
 
-            focus_on_id("input-cell-0");
+            ctx.props()
+                .on_guessed_word_change
+                .emit(self.guessed_word.clone());
+            focus_on_id(&format!("input-cell-{}", first_input(ctx.props())));
         }
     }
 
@@ -93,6 +99,21 @@ impl Component for GridInputComponent {
 
                 let id = format!("input-cell-{cell_number}");
 
+                if let (0, Some(letter)) = (cell_number, ctx.props().first_letter) {
+                    return html! {
+                        <td>
+                            <input
+                                {id}
+                                class="input-cell"
+                                type="text"
+                                value={letter.to_string()}
+                                readonly=true
+                                tabindex="-1"
+                            />
+                        </td>
+                    };
+                }
+
                 html! {
                     <>
                         <td>
@@ -139,16 +160,23 @@ impl Component for GridInputComponent {
                     .emit(self.guessed_word.clone());
             }
             Msg::Validate => {
-                self.guessed_word = String::new();
-                reset_all_inputs(ctx.props().width);
+                self.guessed_word = locked_word(ctx.props());
+                reset_all_inputs(first_input(ctx.props()), ctx.props().width);
 
-                focus_on_id("input-cell-0");
+                focus_on_id(&format!("input-cell-{}", first_input(ctx.props())));
                 ctx.props().on_validate.emit(());
+                ctx.props()
+                    .on_guessed_word_change
+                    .emit(self.guessed_word.clone());
             }
             Msg::NoValidate => {}
             Msg::Back => {
                 if let Some(nb_input) = is_focus_on_input() {
-                    let new_nb_input = if nb_input > 0 { nb_input - 1 } else { nb_input };
+                    let new_nb_input = if nb_input > first_input(ctx.props()) {
+                        nb_input - 1
+                    } else {
+                        nb_input
+                    };
                     let id = format!("input-cell-{new_nb_input}");
                     focus_on_id(&id);
                 }
@@ -158,6 +186,16 @@ impl Component for GridInputComponent {
     }
 }
 
+/// Number of the first cell the player can type in, after the locked first letter if any.
+fn first_input(props: &GridInputProperties) -> usize {
+    usize::from(props.first_letter.is_some())
+}
+
+/// Start of the guess the player can't change.
+fn locked_word(props: &GridInputProperties) -> String {
+    props.first_letter.map(String::from).unwrap_or_default()
+}
+
 fn focus_on_id(id: &str) {
     info!("Focus on {id}");
     let elt = window()
@@ -196,10 +234,10 @@ fn is_focus_on_input() -> Option<usize> {
     }
 }
 
-fn reset_all_inputs(n: usize) {
+fn reset_all_inputs(first: usize, n: usize) {
     info!("Reset all input");
 
-    for i in 0..n {
+    for i in first..n {
         let id = format!("input-cell-{i}");
         let elt = window()
             .expect("no global `window` exists")
@@ -1,6 +1,7 @@
+use crate::components::grid::grid_cell::GridCellComponent;
 use crate::components::grid::grid_input::GridInputComponent;
 use crate::components::grid::grid_line::GridLineComponent;
-use mdla_lib::model::{GuessResponse, Validation};
+use mdla_lib::model::GuessResponse;
 use yew::prelude::*;
 
 mod grid_cell;
@@ -14,6 +15,10 @@ pub struct GridComponent;
 pub struct GridProperties {
     pub past_guesses: Vec<GuessResponse>,
     pub width: usize,
+    pub max_attempts: usize,
+    /// Letter locked in the first cell of the input, if the guesses must start with it
+    pub first_letter: Option<char>,
+    pub finished: bool,
     pub on_guessed_word_change: Callback<String>,
     pub on_validate: Callback<()>,
 }
@@ -33,6 +38,12 @@ impl Component for GridComponent {
     fn view(&self, ctx: &Context<Self>) -> Html {
         let past_guesses = &ctx.props().past_guesses;
         let width = ctx.props().width;
+        let finished = ctx.props().finished;
+        // Rows left empty after the input row, so the player sees how many attempts remain
+        let empty_rows = ctx
+            .props()
+            .max_attempts
+            .saturating_sub(past_guesses.len() + usize::from(!finished));
 
         html! {
             <>
@@ -49,7 +60,7 @@ impl Component for GridComponent {
             }
             // Input grid: displayed if game still going
             {
-                if past_guesses.last().map_or(false, |last_guess| last_guess.validation_list.iter().all(|v| matches!(v, Validation::Correct(_))))
+                if finished
                 {
                     html! {}
                 } else {
@@ -57,6 +68,7 @@ impl Component for GridComponent {
                         <tr>
                             <GridInputComponent
                                 width={width}
+                                first_letter={ctx.props().first_letter}
                                 on_guessed_word_change={ctx.props().on_guessed_word_change.clone()}
                                 on_validate={ctx.props().on_validate.clone()}
                             />
@@ -64,6 +76,12 @@ impl Component for GridComponent {
                     }
                 }
             }
+            // Remaining attempts grid
+            {
+                (0..empty_rows)
+                .map(|_| html! {<tr> {(0..width).map(|_| html! {<GridCellComponent validation={None} />}).collect::<Html>()} </ tr>})
+                .collect::<Html>()
+            }
 
             </table>
             </>
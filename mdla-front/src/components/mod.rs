@@ -1,17 +1,31 @@
-use crate::components::page_game::GamePageComponent;
+use crate::components::{page_archive::ArchivePageComponent, page_game::GamePageComponent};
 
 use log::info;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+mod countdown;
 mod grid;
 mod message_box;
+mod mode_tabs;
+mod page_archive;
 mod page_game;
+mod stats_modal;
 
 #[derive(Routable, Debug, Clone, PartialEq)]
 pub enum AppRoute {
     #[at("/")]
     Index,
+    #[at("/archive")]
+    Archive,
+    #[at("/archive/:puzzle")]
+    ArchivePuzzle { puzzle: i64 },
+    #[at("/lettres/:length")]
+    Length { length: usize },
+    #[at("/entrainement")]
+    Practice,
+    #[at("/c/:id")]
+    Challenge { id: String },
 }
 
 #[derive(Debug)]
@@ -37,7 +51,13 @@ impl Component for MainComponent {
                         <Switch<AppRoute> render={Switch::render(move |routes: &AppRoute| {
                             info!("Route: {:?}", routes);
                             match routes.clone() {
-                                AppRoute::Index => html!{<GamePageComponent/>},
+                                // The key makes a new game page when going from a puzzle to another
+                                AppRoute::Index => html!{<GamePageComponent key={"classic"} puzzle={None} length={None} />},
+                                AppRoute::Archive => html!{<ArchivePageComponent/>},
+                                AppRoute::ArchivePuzzle { puzzle } => html!{<GamePageComponent key={puzzle.to_string()} puzzle={Some(puzzle)} length={None} />},
+                                AppRoute::Length { length } => html!{<GamePageComponent key={format!("length-{length}")} puzzle={None} length={Some(length)} />},
+                                AppRoute::Practice => html!{<GamePageComponent key={"practice"} puzzle={None} length={None} practice=true />},
+                                AppRoute::Challenge { id } => html!{<GamePageComponent key={format!("challenge-{id}")} puzzle={None} length={None} challenge={Some(id.clone())} />},
                             }
                         })} />
                     </div>
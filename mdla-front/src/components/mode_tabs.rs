@@ -0,0 +1,60 @@
+use mdla_lib::model::WORD_LENGTHS;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::AppRoute;
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct ModeTabsProperties {
+    /// Length of the words of the puzzle shown, none for the classic puzzle
+    pub length: Option<usize>,
+    /// Whether a practice game is shown
+    pub practice: bool,
+}
+
+/// Tabs to switch between today's classic puzzle, the puzzles of each length of words and the practice games.
+#[derive(Debug)]
+pub struct ModeTabs;
+
+impl ModeTabs {
+    fn tab(label: String, route: AppRoute, selected: bool) -> Html {
+        let class = if selected { "selected" } else { "" };
+        html! {
+            <li class={class}>
+                <Link<AppRoute> to={route}>{label}</Link<AppRoute>>
+            </li>
+        }
+    }
+}
+
+impl Component for ModeTabs {
+    type Message = ();
+    type Properties = ModeTabsProperties;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let ModeTabsProperties { length, practice } = *ctx.props();
+        html! {
+            <ul class="tabs">
+                {Self::tab("Classique".to_string(), AppRoute::Index, !practice && length.is_none())}
+                {
+                    WORD_LENGTHS.iter().map(|l| {
+                        Self::tab(format!("{l} lettres"), AppRoute::Length { length: *l }, !practice && length == Some(*l))
+                    }).collect::<Html>()
+                }
+                {Self::tab("Entraînement".to_string(), AppRoute::Practice, practice)}
+            </ul>
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: ()) -> bool {
+        false
+    }
+}
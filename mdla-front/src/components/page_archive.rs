@@ -0,0 +1,95 @@
+use log::error;
+use mdla_lib::model::{ArchiveResponse, ArchiveResponseOrError};
+use std::error::Error;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::{components::AppRoute, network::request};
+
+#[derive(Debug)]
+pub enum Msg {
+    GetArchive,
+    GetArchiveResponse(Result<ArchiveResponseOrError, Box<dyn Error>>),
+}
+
+/// List of the past puzzles, to play the ones the player missed.
+#[derive(Debug)]
+pub struct ArchivePageComponent {
+    archive: Option<ArchiveResponse>,
+}
+
+impl Component for ArchivePageComponent {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::GetArchive);
+        Self { archive: None }
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        false
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                <h1>{"Archives"}</h1>
+                <Link<AppRoute> to={AppRoute::Index}>{"Retour au mot du jour"}</Link<AppRoute>>
+                {
+                    match &self.archive {
+                        Some(archive) => html! {
+                            <ul class="archive">
+                                {
+                                    archive.puzzles.iter().map(|puzzle| {
+                                        let (class, status) = match puzzle.won {
+                                            Some(true) => ("won", "trouvé"),
+                                            Some(false) => ("lost", "perdu"),
+                                            None => ("", ""),
+                                        };
+                                        html! {
+                                            <li class={class}>
+                                                <Link<AppRoute> to={AppRoute::ArchivePuzzle { puzzle: puzzle.puzzle_number }}>
+                                                    {format!("MdlA #{} ", puzzle.puzzle_number)}
+                                                </Link<AppRoute>>
+                                                {status}
+                                            </li>
+                                        }
+                                    }).collect::<Html>()
+                                }
+                            </ul>
+                        },
+                        None => html! {},
+                    }
+                }
+            </>
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        log::info!("Message received: {:?}", msg);
+
+        match msg {
+            Msg::GetArchive => {
+                ctx.link().send_future(async move {
+                    match request::<(), ArchiveResponseOrError>("GET", "/api/v1/archive", None)
+                        .await
+                    {
+                        Ok(data) => Msg::GetArchiveResponse(Ok(data)),
+                        Err(err) => Msg::GetArchiveResponse(Err(Box::new(err))),
+                    }
+                });
+            }
+            Msg::GetArchiveResponse(response) => match response {
+                Ok(ArchiveResponseOrError::Response(archive)) => {
+                    self.archive = Some(archive);
+                }
+                response => {
+                    error!("Can't get the archive...: {:?}", response);
+                }
+            },
+        };
+        true
+    }
+}
@@ -1,19 +1,90 @@
 use log::{error, warn};
+use mdla_lib::error::ApiError;
+use mdla_lib::messages::error_message;
 use mdla_lib::model::{
-    AppError, GuessBody, GuessResponse, GuessResponseOrError, HintsResponse, Validation,
+    AnswerResponse, AnswerResponseOrError, AppError, GameOutcome, GameResponseOrError, GuessBody,
+    GuessResponse, GuessResponseOrError, HintsResponse, HintsResponseOrError, StatsResponse,
+    StatsResponseOrError,
 };
+use mdla_lib::share::{game_label, share_text};
 use std::error::Error;
 
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 use crate::{
-    components::{grid::GridComponent, message_box::MessageBox},
+    components::{
+        countdown::CountdownComponent, grid::GridComponent, message_box::MessageBox,
+        mode_tabs::ModeTabs, stats_modal::StatsModal, AppRoute,
+    },
     network::request,
+    storage::{load, save},
 };
 
-const PICTO_RED: char = '🟥';
-const PICTO_YELLOW: char = '🟡';
-const PICTO_BLUE: char = '🟦';
+const GAME_ID_KEY: &str = "mdla-game-id";
+const HARD_MODE_KEY: &str = "mdla-hard-mode";
+
+/// Key of the game id in the local storage: each puzzle of the archive, each length of words and each challenge
+/// has its own game, and so has the practice.
+fn game_id_key(props: &GamePageProperties) -> String {
+    if let Some(challenge) = &props.challenge {
+        return format!("{GAME_ID_KEY}-challenge-{challenge}");
+    }
+    if props.practice {
+        return format!("{GAME_ID_KEY}-practice");
+    }
+    match (props.puzzle, props.length) {
+        (None, None) => GAME_ID_KEY.to_string(),
+        (Some(n), None) => format!("{GAME_ID_KEY}-{n}"),
+        (None, Some(l)) => format!("{GAME_ID_KEY}-length-{l}"),
+        (Some(n), Some(l)) => format!("{GAME_ID_KEY}-{n}-length-{l}"),
+    }
+}
+
+/// Query parameters asking for the game of the page: a challenge, the practice, or a puzzle of the archive
+/// and a length of words, empty for today's classic puzzle.
+fn game_params(props: &GamePageProperties) -> String {
+    if let Some(challenge) = &props.challenge {
+        return format!("challenge={challenge}");
+    }
+    if props.practice {
+        return "practice=true".to_string();
+    }
+    let mut params = vec![];
+    if let Some(n) = props.puzzle {
+        params.push(format!("puzzle={n}"));
+    }
+    if let Some(l) = props.length {
+        params.push(format!("length={l}"));
+    }
+    params.join("&")
+}
+
+/// Key of the number of the puzzle of the game saved under the given key, to reveal its word once it rolled over.
+fn puzzle_number_key(game_id_key: &str) -> String {
+    format!("{game_id_key}-puzzle")
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct GamePageProperties {
+    /// Number of the puzzle of the archive to play, today's puzzle if none
+    pub puzzle: Option<i64>,
+    /// Length of the words of the puzzle, the classic puzzle if none
+    pub length: Option<usize>,
+    /// Play practice games with random words instead of a puzzle
+    #[prop_or_default]
+    pub practice: bool,
+    /// Play the challenge with this id, with a word chosen by another player, instead of a puzzle
+    #[prop_or_default]
+    pub challenge: Option<String>,
+}
+
+impl GamePageProperties {
+    /// Whether the game is the one of a puzzle, counted in the statistics.
+    fn is_puzzle(&self) -> bool {
+        !self.practice && self.challenge.is_none()
+    }
+}
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Message {
@@ -28,13 +99,39 @@ pub enum Severity {
     Error,
 }
 
+/// Word to find of a game and its definition, if any, below the given title.
+fn view_answer(title: Html, answer: &AnswerResponse) -> Html {
+    html! {
+        <div class="answer">
+            <p>{title}</p>
+            {
+                match &answer.definition {
+                    Some(definition) => html! {<p>{definition}</p>},
+                    None => html! {},
+                }
+            }
+        </div>
+    }
+}
+
 #[derive(Debug)]
 pub enum Msg {
+    GetGame(String),
+    GetGameResponse(Result<GameResponseOrError, Box<dyn Error>>),
     GetHints,
-    GetHintsResponse(Result<HintsResponse, Box<dyn Error>>),
+    ToggleHardMode,
+    NewGame,
+    GetHintsResponse(Result<HintsResponseOrError, Box<dyn Error>>),
     UpdateGuess(String),
     PostGuess,
     PostGuessResponse(Result<GuessResponseOrError, Box<dyn Error>>),
+    GetStats,
+    GetStatsResponse(Result<StatsResponseOrError, Box<dyn Error>>),
+    CloseStats,
+    GetAnswer,
+    GetAnswerResponse(Result<AnswerResponseOrError, Box<dyn Error>>),
+    GetPreviousAnswer { game_id: String, puzzle: i64 },
+    GetPreviousAnswerResponse(i64, Result<AnswerResponseOrError, Box<dyn Error>>),
 }
 
 #[derive(Debug)]
@@ -43,43 +140,67 @@ pub struct GamePageComponent {
     past_guesses: Vec<GuessResponse>,
     current_guess: String,
     message: Option<Message>,
+    finished: bool,
+    stats: Option<StatsResponse>,
+    answer: Option<AnswerResponse>,
+    /// Word of the previous game of the page, whose puzzle rolled over before it was restored
+    previous_answer: Option<(i64, AnswerResponse)>,
 }
 
 impl GamePageComponent {
-    fn get_picto_result(&self) -> Vec<String> {
-        let mut result = vec![];
-
-        for guess in self.past_guesses.iter() {
-            let mut line = String::default();
-            for validation in guess.validation_list.iter() {
-                match &validation {
-                    Validation::Correct(_) => line.push(PICTO_RED),
-                    Validation::Present(_) => line.push(PICTO_YELLOW),
-                    Validation::NotInWord(_) => line.push(PICTO_BLUE),
-                }
+    fn apply_outcome(&mut self, outcome: GameOutcome) {
+        match outcome {
+            GameOutcome::Won => {
+                self.finished = true;
+                self.message = Some(self.get_end_message("Bravo ! \\o/".to_string()));
+            }
+            GameOutcome::GameOver { word } => {
+                self.finished = true;
+                self.message = Some(self.get_end_message(format!("Perdu... Le mot était {word}.")));
             }
-            result.push(line)
+            GameOutcome::Ongoing { attempts_left: _ } => {}
         }
-        let mut last_line = String::default();
-        for _ in 0..self.current_guess.len() {
-            last_line.push(PICTO_RED)
+    }
+
+    fn get_end_message(&self, title: String) -> Message {
+        let share = self
+            .hints
+            .as_ref()
+            .map_or(vec![], |h| share_text(h, &self.past_guesses));
+        Message {
+            severity: Severity::Info,
+            text: [
+                vec![
+                    title,
+                    "".to_string(),
+                    "Partage ton score:".to_string(),
+                    "".to_string(),
+                ],
+                share,
+            ]
+            .concat(),
         }
-        result.push(last_line);
-        result
     }
 }
 
 impl Component for GamePageComponent {
     type Message = Msg;
-    type Properties = ();
+    type Properties = GamePageProperties;
 
     fn create(ctx: &Context<Self>) -> Self {
-        ctx.link().send_message(Msg::GetHints);
+        match load(&game_id_key(ctx.props())) {
+            Some(game_id) => ctx.link().send_message(Msg::GetGame(game_id)),
+            None => ctx.link().send_message(Msg::GetHints),
+        }
         Self {
             hints: None,
             past_guesses: vec![],
             current_guess: String::new(),
             message: None,
+            finished: false,
+            stats: None,
+            answer: None,
+            previous_answer: None,
         }
     }
 
@@ -89,21 +210,93 @@ impl Component for GamePageComponent {
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         if let Some(hints) = &self.hints {
+            let first_letter = if hints.first_letter_rule {
+                Some(hints.first_letter)
+            } else {
+                None
+            };
             html! {
                 <>
-                    <h1>{"MdlA"}</h1>
+                    {
+                        if ctx.props().puzzle.is_none() && ctx.props().challenge.is_none() {
+                            html! {<ModeTabs length={ctx.props().length} practice={ctx.props().practice} />}
+                        } else {
+                            html! {}
+                        }
+                    }
+                    <h1>{format!("MdlA {}", game_label(hints))}</h1>
                     <p>{format!("Mot de {} lettres commençant par {}", hints.number_of_letters, hints.first_letter)}</p>
+                    // The key makes a new grid for each game, as the length of the words may change
                     <GridComponent
+                        key={hints.game_id.clone()}
                         width={hints.number_of_letters}
+                        max_attempts={hints.max_attempts}
+                        first_letter={first_letter}
+                        finished={self.finished}
                         past_guesses={self.past_guesses.clone()}
                         on_guessed_word_change={ctx.link().callback(Msg::UpdateGuess)}
                         on_validate={ctx.link().callback(|_| Msg::PostGuess)}
                     />
                     <MessageBox message={self.message.clone()} />
+                    {
+                        match &self.answer {
+                            Some(answer) => view_answer(html! {<strong>{&answer.word}</strong>}, answer),
+                            None => html! {},
+                        }
+                    }
+                    {
+                        match &self.previous_answer {
+                            Some((puzzle, answer)) => view_answer(
+                                html! {<>{format!("Le mot du puzzle #{puzzle} était ")}<strong>{&answer.word}</strong>{"."}</>},
+                                answer,
+                            ),
+                            None => html! {},
+                        }
+                    }
+                    {
+                        // The mode can only be changed before the first guess, by starting the game again
+                        if self.past_guesses.is_empty() && !self.finished {
+                            let onclick = ctx.link().callback(|_| Msg::ToggleHardMode);
+                            let label = if hints.hard { "Mode difficile : activé" } else { "Mode difficile : désactivé" };
+                            html! {<button {onclick}>{label}</button>}
+                        } else if hints.hard {
+                            html! {<p>{"Mode difficile"}</p>}
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        if ctx.props().practice {
+                            let onclick = ctx.link().callback(|_| Msg::NewGame);
+                            html! {<button {onclick}>{"Nouvelle partie"}</button>}
+                        } else if self.finished && ctx.props().is_puzzle() && ctx.props().puzzle.is_none() {
+                            html! {<CountdownComponent target={hints.next_rollover} />}
+                        } else {
+                            html! {}
+                        }
+                    }
+                    {
+                        match &self.stats {
+                            Some(stats) => html! {
+                                <StatsModal stats={stats.clone()} on_close={ctx.link().callback(|_| Msg::CloseStats)} />
+                            },
+                            None => html! {},
+                        }
+                    }
+                    <p>
+                        {
+                            match ctx.props().puzzle {
+                                Some(_) => html! {<Link<AppRoute> to={AppRoute::Index}>{"Mot du jour"}</Link<AppRoute>>},
+                                None => html! {},
+                            }
+                        }
+                        {" "}
+                        <Link<AppRoute> to={AppRoute::Archive}>{"Archives"}</Link<AppRoute>>
+                    </p>
                 </>
             }
         } else {
-            html! {}
+            html! {<MessageBox message={self.message.clone()} />}
         }
     }
 
@@ -111,21 +304,103 @@ impl Component for GamePageComponent {
         log::info!("Message received: {:?}", msg);
 
         match msg {
+            Msg::GetGame(game_id) => {
+                let params = game_params(ctx.props());
+                ctx.link().send_future(async move {
+                    let url = format!("/api/v1/game?game_id={game_id}&{params}");
+                    match request::<(), GameResponseOrError>("GET", &url, None).await {
+                        Ok(data) => Msg::GetGameResponse(Ok(data)),
+                        Err(err) => Msg::GetGameResponse(Err(Box::new(err))),
+                    }
+                });
+            }
+            Msg::GetGameResponse(response) => match response {
+                Ok(GameResponseOrError::Response(game)) => {
+                    self.hints = Some(game.hints);
+                    self.past_guesses = game.past_guesses;
+
+                    if let Some(outcome) = self.past_guesses.last().map(|g| g.outcome.clone()) {
+                        self.apply_outcome(outcome);
+                    }
+                    if self.finished {
+                        ctx.link().send_message(Msg::GetAnswer);
+                    }
+                    if self.finished && ctx.props().is_puzzle() {
+                        ctx.link().send_message(Msg::GetStats);
+                    }
+                }
+                Ok(GameResponseOrError::Error(app_error)) => {
+                    // The game has expired, let's start a new one
+                    warn!("Can't restore game...: {:?}", app_error);
+                    // Today's puzzle rolled over since the game was saved: reveal the word of its puzzle
+                    let key = game_id_key(ctx.props());
+                    let saved_puzzle = load(&puzzle_number_key(&key)).and_then(|n| n.parse().ok());
+                    if let (None, Some(game_id), Some(puzzle)) =
+                        (ctx.props().puzzle, load(&key), saved_puzzle)
+                    {
+                        if ctx.props().is_puzzle() {
+                            ctx.link()
+                                .send_message(Msg::GetPreviousAnswer { game_id, puzzle });
+                        }
+                    }
+                    ctx.link().send_message(Msg::GetHints);
+                }
+                Err(e) => {
+                    error!("Something terrible happened...: {:?}", e);
+                    ctx.link().send_message(Msg::GetHints);
+                }
+            },
             Msg::GetHints => {
+                let hard = load(HARD_MODE_KEY).as_deref() == Some("true");
+                let url = if ctx.props().practice {
+                    format!("/api/v1/practice?hard={hard}")
+                } else {
+                    format!("/api/v1/hints?{}&hard={hard}", game_params(ctx.props()))
+                };
                 ctx.link().send_future(async move {
-                    match request::<(), HintsResponse>("GET", "/api/hints", None).await {
+                    match request::<(), HintsResponseOrError>("GET", &url, None).await {
                         Ok(data) => Msg::GetHintsResponse(Ok(data)),
                         Err(err) => Msg::GetHintsResponse(Err(Box::new(err))),
                     }
                 });
             }
+            Msg::ToggleHardMode => {
+                let hard = matches!(&self.hints, Some(hints) if hints.hard);
+                save(HARD_MODE_KEY, &(!hard).to_string());
+                ctx.link().send_message(Msg::GetHints);
+            }
+            Msg::NewGame => {
+                self.past_guesses = vec![];
+                self.current_guess = String::new();
+                self.message = None;
+                self.finished = false;
+                self.stats = None;
+                self.answer = None;
+                ctx.link().send_message(Msg::GetHints);
+            }
             Msg::GetHintsResponse(response) => match response {
-                Ok(hints) => {
+                Ok(HintsResponseOrError::Response(hints)) => {
+                    let key = game_id_key(ctx.props());
+                    // The server resumes a puzzle already started by the player: restore its guesses
+                    if ctx.props().is_puzzle() && load(&key).as_ref() != Some(&hints.game_id) {
+                        ctx.link().send_message(Msg::GetGame(hints.game_id.clone()));
+                    }
+                    save(&key, &hints.game_id);
+                    save(&puzzle_number_key(&key), &hints.puzzle_number.to_string());
                     self.hints = Some(hints);
                 }
-                Err(e) => {
-                    error!("Something terrible happened...: {:?}", e);
+                response => {
+                    error!("Something terrible happened...: {:?}", response);
                     self.hints = None;
+                    let text = if ctx.props().challenge.is_some() {
+                        "Ce défi n'existe pas."
+                    } else {
+                        "Ce puzzle n'est pas disponible."
+                    };
+                    self.message = Some(Message {
+                        severity: Severity::Error,
+                        text: vec![text.to_string()],
+                    });
                 }
             },
             Msg::UpdateGuess(guess) => {
@@ -135,12 +410,25 @@ impl Component for GamePageComponent {
                 self.message = None;
 
                 let current_guess = self.current_guess.clone();
+                let puzzle = ctx.props().puzzle;
+                let length = ctx.props().length;
+                let practice = ctx.props().practice;
+                let challenge = ctx.props().challenge.clone();
+                let game_id = self
+                    .hints
+                    .as_ref()
+                    .map_or(String::new(), |h| h.game_id.clone());
 
                 ctx.link().send_future(async move {
                     match request::<GuessBody, GuessResponseOrError>(
                         "POST",
-                        "/api/guess",
+                        "/api/v1/guess",
                         Some(GuessBody {
+                            game_id,
+                            puzzle,
+                            length,
+                            practice,
+                            challenge,
                             guess: current_guess,
                         }),
                     )
@@ -151,53 +439,118 @@ impl Component for GamePageComponent {
                     }
                 });
             }
-            Msg::PostGuessResponse(response) => {
-                match response {
-                    Ok(GuessResponseOrError::Response(guess_response)) => {
-                        let correct_guess = guess_response
-                            .clone()
-                            .validation_list
-                            .into_iter()
-                            .all(|v| matches!(v, Validation::Correct(_)));
-                        if correct_guess {
-                            self.message = Some(Message {
-                                severity: Severity::Info,
-                                text: [
-                                    vec![
-                                        "Bravo ! \\o/".to_string(),
-                                        "".to_string(),
-                                        "Partage ton score:".to_string(),
-                                        "".to_string(),
-                                    ],
-                                    self.get_picto_result(),
-                                ]
-                                .concat(),
-                            })
-                        }
-
-                        self.past_guesses.push(guess_response);
+            Msg::PostGuessResponse(response) => match response {
+                Ok(GuessResponseOrError::Response(guess_response)) => {
+                    let outcome = guess_response.outcome.clone();
+                    self.past_guesses.push(guess_response);
+                    self.apply_outcome(outcome);
+                    if self.finished {
+                        ctx.link().send_message(Msg::GetAnswer);
                     }
-                    Ok(GuessResponseOrError::Error(app_error)) => {
-                        warn!("Bad request...: {:?}", app_error);
-                        match app_error{
-                            AppError::WordNotInDictionary(w) => {
-                                self.message = Some(Message{severity:Severity::Warn, text:vec![format!("Le mot {w} n'est pas dans notre dictionnaire.")]})
-                            }
-                            AppError::BadWordLength { size_expected: se, size_received: sr, word_sent: w } => {
-                                self.message = Some(Message{severity:Severity::Warn, text:vec![format!("Le mot {w} a {sr} lettres mais le mot a deviner doit en avoir {se}.")]})
-                            }
+                    if self.finished && ctx.props().is_puzzle() {
+                        ctx.link().send_message(Msg::GetStats);
+                    }
+                }
+                Ok(GuessResponseOrError::Error(ApiError::App(app_error))) => {
+                    warn!("Bad request...: {:?}", app_error);
+                    let text = match app_error {
+                        AppError::GameNotFound(_) => {
+                            "Cette partie n'existe plus, recharge la page pour en commencer une nouvelle."
+                                .to_string()
                         }
+                        AppError::GameFinished => {
+                            self.finished = true;
+                            error_message(&app_error)
+                        }
+                        _ => error_message(&app_error),
+                    };
+                    self.message = Some(Message {
+                        severity: Severity::Warn,
+                        text: vec![text],
+                    });
+                }
+                response => {
+                    error!("Something terrible happened...: {:?}", response);
+                    self.message = Some(Message {
+                        severity: Severity::Error,
+                        text: vec!["Quelque chose cloche... Reviens dans quelques minutes le temps que le serveur revienne de vacances ! :)".to_string()],
+                    })
+                }
+            },
+            Msg::GetStats => {
+                // Each length of words has its own statistics
+                let url = match ctx.props().length {
+                    Some(l) => format!("/api/v1/stats?length={l}"),
+                    None => "/api/v1/stats".to_string(),
+                };
+                ctx.link().send_future(async move {
+                    match request::<(), StatsResponseOrError>("GET", &url, None).await {
+                        Ok(data) => Msg::GetStatsResponse(Ok(data)),
+                        Err(err) => Msg::GetStatsResponse(Err(Box::new(err))),
                     }
-                    Err(e) => {
-                        error!("Something terrible happened...: {:?}", e);
-                        self.message = Some(Message {
-                            severity: Severity::Error,
-                            text: vec![format!("Quelque chose cloche... Reviens dans quelques minutes le temps que le serveur revienne de vacances ! :)")],
-                        })
+                });
+            }
+            Msg::GetStatsResponse(response) => match response {
+                Ok(StatsResponseOrError::Response(stats)) => {
+                    self.stats = Some(stats);
+                }
+                response => {
+                    error!("Can't get the stats...: {:?}", response);
+                }
+            },
+            Msg::CloseStats => {
+                self.stats = None;
+            }
+            Msg::GetAnswer => {
+                let game_id = self
+                    .hints
+                    .as_ref()
+                    .map_or(String::new(), |h| h.game_id.clone());
+                let url = format!(
+                    "/api/v1/answer?game_id={game_id}&{}",
+                    game_params(ctx.props())
+                );
+                ctx.link().send_future(async move {
+                    match request::<(), AnswerResponseOrError>("GET", &url, None).await {
+                        Ok(data) => Msg::GetAnswerResponse(Ok(data)),
+                        Err(err) => Msg::GetAnswerResponse(Err(Box::new(err))),
                     }
+                });
+            }
+            Msg::GetAnswerResponse(response) => match response {
+                Ok(AnswerResponseOrError::Response(answer)) => {
+                    self.answer = Some(answer);
                 }
-                self.current_guess = String::new();
+                Ok(AnswerResponseOrError::Error(app_error)) => {
+                    warn!("Can't get the answer...: {:?}", app_error);
+                }
+                Err(e) => {
+                    error!("Can't get the answer...: {:?}", e);
+                }
+            },
+            Msg::GetPreviousAnswer { game_id, puzzle } => {
+                let mut url = format!("/api/v1/answer?game_id={game_id}&puzzle={puzzle}");
+                if let Some(l) = ctx.props().length {
+                    url.push_str(&format!("&length={l}"));
+                }
+                ctx.link().send_future(async move {
+                    match request::<(), AnswerResponseOrError>("GET", &url, None).await {
+                        Ok(data) => Msg::GetPreviousAnswerResponse(puzzle, Ok(data)),
+                        Err(err) => Msg::GetPreviousAnswerResponse(puzzle, Err(Box::new(err))),
+                    }
+                });
             }
+            Msg::GetPreviousAnswerResponse(puzzle, response) => match response {
+                Ok(AnswerResponseOrError::Response(answer)) => {
+                    self.previous_answer = Some((puzzle, answer));
+                }
+                response => {
+                    warn!(
+                        "Can't get the answer of the previous puzzle...: {:?}",
+                        response
+                    );
+                }
+            },
         };
         true
     }
@@ -0,0 +1,73 @@
+use mdla_lib::model::StatsResponse;
+use yew::prelude::*;
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct StatsModalProperties {
+    pub stats: StatsResponse,
+    pub on_close: Callback<()>,
+}
+
+#[derive(Debug)]
+pub struct StatsModal;
+
+impl Component for StatsModal {
+    type Message = ();
+    type Properties = StatsModalProperties;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>) -> bool {
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let stats = &ctx.props().stats;
+        let max_count = stats
+            .guess_distribution
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let onclick = ctx.props().on_close.reform(|_| ());
+
+        html! {
+            <div class="modal">
+                <h2>{"Statistiques"}</h2>
+                <table class="stats">
+                    <tr>
+                        <td>{stats.games_played}</td>
+                        <td>{stats.win_percentage}</td>
+                        <td>{stats.current_streak}</td>
+                        <td>{stats.max_streak}</td>
+                    </tr>
+                    <tr class="stats-label">
+                        <td>{"Parties"}</td>
+                        <td>{"% de victoires"}</td>
+                        <td>{"Série actuelle"}</td>
+                        <td>{"Meilleure série"}</td>
+                    </tr>
+                </table>
+                <h3>{"Répartition des essais"}</h3>
+                {
+                    stats.guess_distribution.iter().enumerate().map(|(i, count)| {
+                        let width = format!("width: {}%", 10 + 90 * count / max_count);
+                        html! {
+                            <div class="histogram-line">
+                                <span>{i + 1}</span>
+                                <div class="histogram-bar" style={width}>{count}</div>
+                            </div>
+                        }
+                    }).collect::<Html>()
+                }
+                <button {onclick}>{"Fermer"}</button>
+            </div>
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, _msg: ()) -> bool {
+        false
+    }
+}
@@ -2,6 +2,7 @@ use crate::components::MainComponent;
 
 mod components;
 mod network;
+mod storage;
 
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
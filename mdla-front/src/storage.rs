@@ -0,0 +1,27 @@
+use log::warn;
+use web_sys::Storage;
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()
+        .expect("no window available")
+        .local_storage()
+        .ok()
+        .flatten()
+}
+
+/// Read a value saved in the local storage of the browser.
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+/// Save a value in the local storage of the browser, so it survives a reload of the page.
+pub fn save(key: &str, value: &str) {
+    match local_storage() {
+        Some(storage) => {
+            if let Err(e) = storage.set_item(key, value) {
+                warn!("Can't save {key}: {e:?}");
+            }
+        }
+        None => warn!("No local storage available, {key} won't be saved"),
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::model::AppError;
+
+/// Body of every error answered by the API, whatever failed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ErrorEnvelope {
+    /// Machine-readable code of the error: the code of an [`AppError`], `bad_request`, `not_found`,
+    /// `unauthorized` or `internal_error`
+    pub code: String,
+    /// Description of the error, in English
+    pub message: String,
+    /// Details of the error, depending on its code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+/// An error answered by the API, decoded from its [`ErrorEnvelope`].
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(from = "ErrorEnvelope", into = "ErrorEnvelope")]
+pub enum ApiError {
+    /// An error of the player
+    App(AppError),
+    /// The request can't be read: malformed JSON, wrong content type, missing parameter…
+    BadRequest(String),
+    /// There is nothing at this path
+    NotFound(String),
+    /// The admin token is missing or wrong
+    Unauthorized,
+    /// Something failed on the server
+    Internal(String),
+    /// An error unknown to this version of the model, from a more recent server
+    Other(ErrorEnvelope),
+}
+
+impl From<AppError> for ApiError {
+    fn from(error: AppError) -> Self {
+        ApiError::App(error)
+    }
+}
+
+impl From<ErrorEnvelope> for ApiError {
+    fn from(envelope: ErrorEnvelope) -> Self {
+        match envelope.code.as_str() {
+            "bad_request" => ApiError::BadRequest(envelope.message),
+            "not_found" => ApiError::NotFound(envelope.message),
+            "unauthorized" => ApiError::Unauthorized,
+            "internal_error" => ApiError::Internal(envelope.message),
+            _ => {
+                let error = json!({"code": envelope.code, "details": envelope.details});
+                match serde_json::from_value(error) {
+                    Ok(app_error) => ApiError::App(app_error),
+                    Err(_) => ApiError::Other(envelope),
+                }
+            }
+        }
+    }
+}
+
+impl From<ApiError> for ErrorEnvelope {
+    fn from(error: ApiError) -> Self {
+        let message = error.to_string();
+        let (code, details) = match error {
+            ApiError::App(app_error) => match serde_json::to_value(app_error) {
+                Ok(Value::Object(mut tagged)) => (
+                    tagged
+                        .remove("code")
+                        .and_then(|code| code.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                    tagged.remove("details"),
+                ),
+                _ => unreachable!("an AppError is serialized as an object"),
+            },
+            ApiError::BadRequest(_) => ("bad_request".to_string(), None),
+            ApiError::NotFound(_) => ("not_found".to_string(), None),
+            ApiError::Unauthorized => ("unauthorized".to_string(), None),
+            ApiError::Internal(_) => ("internal_error".to_string(), None),
+            ApiError::Other(envelope) => return envelope,
+        };
+        ErrorEnvelope {
+            code,
+            message,
+            details,
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for ApiError {
+    fn schema_name() -> String {
+        ErrorEnvelope::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        ErrorEnvelope::json_schema(gen)
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::App(app_error) => write!(f, "{app_error}"),
+            ApiError::BadRequest(message)
+            | ApiError::NotFound(message)
+            | ApiError::Internal(message) => write!(f, "{message}"),
+            ApiError::Unauthorized => write!(f, "Missing or wrong admin token"),
+            ApiError::Other(envelope) => write!(f, "{}", envelope.message),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::BadWordLength {
+                size_expected,
+                size_received,
+                word_sent,
+            } => {
+                write!(f, "Word should be {size_expected} letters but received {size_received} : {word_sent}")
+            }
+            AppError::WordNotInDictionary(w) => write!(f, "Word {w} is not in our dictionary"),
+            AppError::InvalidCharacters { positions, chars } => {
+                write!(
+                    f,
+                    "Characters {chars:?} at positions {positions:?} are not letters"
+                )
+            }
+            AppError::WrongFirstLetter { expected, received } => {
+                write!(
+                    f,
+                    "Word should start with {expected} but starts with {received}"
+                )
+            }
+            AppError::HardModeViolation {
+                letter,
+                position: Some(position),
+            } => {
+                write!(
+                    f,
+                    "Hard mode: letter {letter} must be at position {position}"
+                )
+            }
+            AppError::HardModeViolation {
+                letter,
+                position: None,
+            } => write!(f, "Hard mode: letter {letter} must be in the word"),
+            AppError::GameNotFound(id) => write!(f, "Game {id} does not exist or is over"),
+            AppError::GameFinished => write!(f, "Game is finished, no more guesses allowed"),
+            AppError::PuzzleNotAvailable(n) => write!(f, "Puzzle {n} is not available"),
+            AppError::LengthNotAvailable(length) => {
+                write!(f, "No puzzle with words of {length} letters")
+            }
+            AppError::ChallengeNotFound(id) => write!(f, "Challenge {id} not found"),
+            AppError::GameNotFinished => {
+                write!(f, "The answer is only given once the game is over")
+            }
+            AppError::WordNotInGame(word) => write!(f, "Word {word} was not played in this game"),
+            AppError::TooManyChallenges => {
+                write!(f, "Too many challenges created today, try again tomorrow")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_of_app_error() {
+        let error = ApiError::App(AppError::WordNotInDictionary("ABCDEF".to_string()));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "word_not_in_dictionary",
+                "message": "Word ABCDEF is not in our dictionary",
+                "details": "ABCDEF"
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ApiError::App(AppError::GameFinished)).unwrap(),
+            json!({
+                "code": "game_finished",
+                "message": "Game is finished, no more guesses allowed"
+            })
+        );
+
+        assert_eq!(
+            serde_json::from_str::<ApiError>(r#"{"code": "game_finished", "message": ""}"#)
+                .unwrap(),
+            ApiError::App(AppError::GameFinished)
+        );
+        let json = r#"{"code": "hard_mode_violation", "message": "Hard mode", "details": {"letter": "A", "position": null}}"#;
+        assert_eq!(
+            serde_json::from_str::<ApiError>(json).unwrap(),
+            ApiError::App(AppError::HardModeViolation {
+                letter: 'A',
+                position: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_envelope_of_other_errors() {
+        let error = ApiError::BadRequest("Json deserialize error: EOF".to_string());
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json,
+            json!({"code": "bad_request", "message": "Json deserialize error: EOF"})
+        );
+        assert_eq!(serde_json::from_value::<ApiError>(json).unwrap(), error);
+
+        let json = r#"{"code": "internal_error", "message": "No word today"}"#;
+        assert_eq!(
+            serde_json::from_str::<ApiError>(json).unwrap(),
+            ApiError::Internal("No word today".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<ApiError>(r#"{"code": "unauthorized", "message": ""}"#).unwrap(),
+            ApiError::Unauthorized
+        );
+    }
+
+    #[test]
+    fn test_envelope_of_unknown_error() {
+        // An error added in a later version of the server, or an app error with unexpected details
+        for json in [
+            json!({"code": "too_many_games", "message": "Slow down", "details": 3}),
+            json!({"code": "game_not_found", "message": "Game not found", "details": [1]}),
+        ] {
+            let error = serde_json::from_value::<ApiError>(json.clone()).unwrap();
+            assert!(matches!(&error, ApiError::Other(envelope) if envelope.code == json["code"]));
+            assert_eq!(serde_json::to_value(&error).unwrap(), json);
+        }
+    }
+}
@@ -1 +1,7 @@
+pub mod error;
+pub mod messages;
 pub mod model;
+pub mod normalize;
+pub mod scoring;
+pub mod share;
+pub mod validation;
@@ -0,0 +1,78 @@
+use crate::model::AppError;
+
+/// Message of an error of the player, in French like the rest of the game, for the web front and the CLI.
+pub fn error_message(error: &AppError) -> String {
+    match error {
+        AppError::WordNotInDictionary(w) => format!("Le mot {w} n'est pas dans notre dictionnaire."),
+        AppError::BadWordLength {
+            size_expected,
+            size_received,
+            word_sent,
+        } => format!(
+            "Le mot {word_sent} a {size_received} lettres mais le mot à deviner doit en avoir {size_expected}."
+        ),
+        AppError::InvalidCharacters { positions: _, chars } => {
+            let chars: Vec<String> = chars.iter().map(|c| format!("« {c} »")).collect();
+            format!(
+                "Le mot ne doit contenir que des lettres, pas {}.",
+                chars.join(", ")
+            )
+        }
+        AppError::WrongFirstLetter {
+            expected,
+            received: _,
+        } => format!("Le mot doit commencer par {expected}."),
+        AppError::HardModeViolation {
+            letter,
+            position: Some(position),
+        } => format!(
+            "Mode difficile : la lettre {letter} doit rester en position {}.",
+            position + 1
+        ),
+        AppError::HardModeViolation {
+            letter,
+            position: None,
+        } => format!("Mode difficile : le mot doit contenir la lettre {letter}."),
+        AppError::GameNotFound(_) => "Cette partie n'existe plus.".to_string(),
+        AppError::GameFinished => "Cette partie est terminée !".to_string(),
+        AppError::PuzzleNotAvailable(n) => format!("Le puzzle #{n} n'est pas disponible."),
+        AppError::LengthNotAvailable(l) => {
+            format!("Il n'y a pas de puzzle avec des mots de {l} lettres.")
+        }
+        AppError::ChallengeNotFound(_) => "Ce défi n'existe pas.".to_string(),
+        AppError::GameNotFinished => "Cette partie n'est pas terminée.".to_string(),
+        AppError::WordNotInGame(w) => format!("Le mot {w} n'a pas été joué dans cette partie."),
+        AppError::TooManyChallenges => {
+            "Trop de défis ont été créés aujourd'hui, réessaie demain.".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(&AppError::BadWordLength {
+                size_expected: 7,
+                size_received: 6,
+                word_sent: "ABAISS".to_string()
+            }),
+            "Le mot ABAISS a 6 lettres mais le mot à deviner doit en avoir 7."
+        );
+        assert_eq!(
+            error_message(&AppError::WordNotInDictionary("ABCDEF".to_string())),
+            "Le mot ABCDEF n'est pas dans notre dictionnaire."
+        );
+        // Positions start at 1 for the player
+        assert_eq!(
+            error_message(&AppError::HardModeViolation {
+                letter: 'A',
+                position: Some(0)
+            }),
+            "Mode difficile : la lettre A doit rester en position 1."
+        );
+    }
+}
@@ -1,6 +1,17 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::error::ApiError;
+
+/// Lengths of the words of the puzzles of each length. The classic puzzle mixes all of them.
+pub const WORD_LENGTHS: [usize; 4] = [6, 7, 8, 9];
+
+/// An error of the player, serialized with its code and its details if any:
+/// `{"code": "word_not_in_dictionary", "details": "ABCDEF"}`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "code", content = "details", rename_all = "snake_case")]
 pub enum AppError {
     BadWordLength {
         size_expected: usize,
@@ -8,38 +19,317 @@ pub enum AppError {
         word_sent: String,
     },
     WordNotInDictionary(String),
+    /// Characters of the guess that are not letters, with their positions in the guess
+    InvalidCharacters {
+        positions: Vec<usize>,
+        chars: Vec<char>,
+    },
+    /// The guess must start with the first letter of the word, as in Motus
+    WrongFirstLetter {
+        expected: char,
+        received: char,
+    },
+    /// In hard mode, a letter found by a previous guess is missing: it must be at the given position
+    /// if it was correct, or anywhere in the guess if it was present
+    HardModeViolation {
+        letter: char,
+        position: Option<usize>,
+    },
+    GameNotFound(String),
+    GameFinished,
+    /// The puzzle asked for is not published yet, or does not exist
+    PuzzleNotAvailable(i64),
+    /// There is no puzzle with words of this length
+    LengthNotAvailable(usize),
+    /// The challenge does not exist
+    ChallengeNotFound(String),
+    /// The answer is only given once the game is finished, or its day is over
+    GameNotFinished,
+    /// Definitions are only given for the word to find and the guesses of the game
+    WordNotInGame(String),
+    /// Too many challenges were created today, by the same client or by all of them
+    TooManyChallenges,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GuessResponse {
     pub validation_list: Vec<Validation>,
+    pub outcome: GameOutcome,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "status", content = "details", rename_all = "snake_case")]
+pub enum GameOutcome {
+    Ongoing { attempts_left: usize },
+    Won,
+    GameOver { word: String },
+}
+
+/// Answer of the API, serialized as `{"status": "ok", "data": …}`, or `{"status": "error", "data": …}` with the
+/// envelope of an [`ApiError`].
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "status", content = "data", rename_all = "snake_case")]
+pub enum ResponseOrError<T> {
+    #[serde(rename = "ok")]
+    Response(T),
+    Error(ApiError),
+}
+
+pub type GuessResponseOrError = ResponseOrError<GuessResponse>;
+
 #[derive(Debug, Serialize, Deserialize)]
-pub enum GuessResponseOrError {
-    Response(GuessResponse),
-    Error(AppError),
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GameResponse {
+    pub hints: HintsResponse,
+    pub past_guesses: Vec<GuessResponse>,
 }
 
+pub type GameResponseOrError = ResponseOrError<GameResponse>;
+
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HintsResponse {
+    pub game_id: String,
+    pub puzzle_number: i64,
+    /// When the next puzzle will be available, as a Unix timestamp in seconds
+    pub next_rollover: i64,
     pub number_of_letters: usize,
     pub first_letter: char,
+    /// Whether the guesses must start with the first letter
+    pub first_letter_rule: bool,
+    /// Whether the game is in hard mode, where the letters found must be used in the next guesses
+    pub hard: bool,
+    /// Length of the words of the puzzle, none for the classic puzzle
+    pub length: Option<usize>,
+    /// Whether the game is a practice one, with a random word instead of the word of a puzzle
+    pub practice: bool,
+    /// Challenge played, with a word chosen by another player instead of the word of a puzzle
+    pub challenge: Option<String>,
+    pub max_attempts: usize,
 }
 
+pub type HintsResponseOrError = ResponseOrError<HintsResponse>;
+
+/// Validation of a letter of a guess, serialized as `{"status": "correct", "letter": "A"}`.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "status", content = "letter", rename_all = "snake_case")]
 pub enum Validation {
     Correct(char),
     Present(char),
     NotInWord(char),
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StatsResponse {
+    pub games_played: usize,
+    pub win_percentage: u8,
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// Number of games won for each number of guesses, starting at 1 guess
+    pub guess_distribution: Vec<usize>,
+}
+
+pub type StatsResponseOrError = ResponseOrError<StatsResponse>;
+
+/// A past puzzle, as listed in the archive.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ArchivePuzzle {
+    pub puzzle_number: i64,
+    /// Whether the player won or lost the puzzle, none if they have not finished it
+    pub won: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ArchiveResponse {
+    /// All the puzzles before today's one, the most recent first
+    pub puzzles: Vec<ArchivePuzzle>,
+}
+
+pub type ArchiveResponseOrError = ResponseOrError<ArchiveResponse>;
+
+/// The puzzle to play: today's one if none, or a past one from the archive.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HintsQuery {
+    pub puzzle: Option<i64>,
+    /// The puzzle with words of this length, the classic puzzle if none
+    pub length: Option<usize>,
+    /// Play this challenge instead of a puzzle, the puzzle and length are then ignored
+    pub challenge: Option<String>,
+    /// Start the game in hard mode
+    #[serde(default)]
+    pub hard: bool,
+}
+
+/// A new practice game, with a random word of the given length, or of any length if none.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PracticeQuery {
+    pub length: Option<usize>,
+    /// Start the game in hard mode
+    #[serde(default)]
+    pub hard: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GameQuery {
+    pub game_id: String,
+    pub puzzle: Option<i64>,
+    pub length: Option<usize>,
+    /// The game is a practice one, the puzzle and length are then ignored
+    #[serde(default)]
+    pub practice: bool,
+    /// The game is one of this challenge, the puzzle and length are then ignored
+    pub challenge: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GuessBody {
+    pub game_id: String,
+    pub puzzle: Option<i64>,
+    pub length: Option<usize>,
+    /// The game is a practice one, the puzzle and length are then ignored
+    #[serde(default)]
+    pub practice: bool,
+    /// The game is one of this challenge, the puzzle and length are then ignored
+    pub challenge: Option<String>,
     pub guess: String,
 }
 
+/// The word to find of a game, revealed once it is over.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AnswerResponse {
+    pub word: String,
+    /// Definition of the word, if the server has one
+    pub definition: Option<String>,
+}
+
+pub type AnswerResponseOrError = ResponseOrError<AnswerResponse>;
+
+/// Definition of a word played in a finished game.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DefinitionResponse {
+    pub word: String,
+    /// Definition of the word, if the server has one
+    pub definition: Option<String>,
+}
+
+pub type DefinitionResponseOrError = ResponseOrError<DefinitionResponse>;
+
+/// A word chosen by a player for their friends to find.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChallengeBody {
+    pub word: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ChallengeResponse {
+    /// Id of the challenge, to share with the friends: it does not reveal the word
+    pub challenge_id: String,
+}
+
+pub type ChallengeResponseOrError = ResponseOrError<ChallengeResponse>;
+
+/// The puzzles to get the statistics or the archive of: the ones with words of this length,
+/// the classic ones if none.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ModeQuery {
+    pub length: Option<usize>,
+}
+
+/// Sizes of the word lists, once reloaded by an admin.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReloadResponse {
+    /// Number of words accepted as a guess
+    pub all_words: usize,
+    /// Number of words that can be picked as a daily word
+    pub playable_words: usize,
+}
+
+pub type ReloadResponseOrError = ResponseOrError<ReloadResponse>;
+
+/// Dictionaries of the server, loaded once at startup and shared by all the workers.
+#[derive(Debug)]
 pub struct AppState {
-    pub all_word_list: Vec<String>,
+    /// Every word accepted as a guess
+    pub all_word_list: HashSet<String>,
+    /// Words that can be picked as a daily word
     pub playable_word_list: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_json_is_tagged() {
+        let response = GuessResponseOrError::Response(GuessResponse {
+            validation_list: vec![Validation::Correct('A'), Validation::NotInWord('B')],
+            outcome: GameOutcome::Ongoing { attempts_left: 5 },
+        });
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "status": "ok",
+                "data": {
+                    "validation_list": [
+                        {"status": "correct", "letter": "A"},
+                        {"status": "not_in_word", "letter": "B"}
+                    ],
+                    "outcome": {"status": "ongoing", "details": {"attempts_left": 5}}
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(&GameOutcome::Won).unwrap(),
+            json!({"status": "won"})
+        );
+
+        let error =
+            GuessResponseOrError::Error(AppError::WordNotInDictionary("ABCDEF".to_string()).into());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "status": "error",
+                "data": {
+                    "code": "word_not_in_dictionary",
+                    "message": "Word ABCDEF is not in our dictionary",
+                    "details": "ABCDEF"
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(&AppError::GameFinished).unwrap(),
+            json!({"code": "game_finished"})
+        );
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let json = r#"{"status": "error", "data": {"code": "hard_mode_violation", "message": "Hard mode", "details": {"letter": "A", "position": 0}}}"#;
+        assert!(matches!(
+            serde_json::from_str(json).unwrap(),
+            GuessResponseOrError::Error(ApiError::App(AppError::HardModeViolation {
+                letter: 'A',
+                position: Some(0)
+            }))
+        ));
+    }
+}
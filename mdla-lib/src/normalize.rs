@@ -0,0 +1,151 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+use crate::model::AppError;
+
+/// Characters of a word that are not letters, with their position in the word as typed, surrounding whitespace
+/// included. Accents typed as combining marks belong to the letter before them, they have no position of their own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidCharacters(pub Vec<(usize, char)>);
+
+impl From<InvalidCharacters> for AppError {
+    fn from(invalid: InvalidCharacters) -> Self {
+        let (positions, chars) = invalid.0.into_iter().unzip();
+        AppError::InvalidCharacters { positions, chars }
+    }
+}
+
+/// Normalize a word, typed by a player or read from a words file, to the letters `A` to `Z` used by the game.
+///
+/// Surrounding whitespace is trimmed, case and diacritics are dropped and ligatures are expanded:
+/// `" Cœur  "`, `"COEUR"` and `"cœur"` are all `"COEUR"`. Any other character is invalid.
+pub fn normalize(word: &str) -> Result<String, InvalidCharacters> {
+    let (normalized, invalid) = normalize_letters(word);
+
+    if invalid.is_empty() {
+        Ok(normalized)
+    } else {
+        Err(InvalidCharacters(invalid))
+    }
+}
+
+/// Normalize the letters of a word, setting aside the invalid characters.
+pub(crate) fn normalize_letters(word: &str) -> (String, Vec<(usize, char)>) {
+    let mut normalized = String::with_capacity(word.len());
+    let mut invalid = vec![];
+
+    let mut position = word.chars().take_while(|c| c.is_whitespace()).count();
+    for c in word.trim().chars() {
+        if is_combining_mark(c) {
+            continue;
+        }
+        let letters: String = match c {
+            'œ' | 'Œ' => "OE".to_string(),
+            'æ' | 'Æ' => "AE".to_string(),
+            _ => c
+                .nfkd()
+                .filter(|c| !is_combining_mark(*c))
+                .flat_map(char::to_uppercase)
+                .collect(),
+        };
+
+        if letters.chars().all(|l| l.is_ascii_uppercase()) {
+            normalized.push_str(&letters);
+        } else {
+            invalid.push((position, c));
+        }
+        position += 1;
+    }
+
+    (normalized, invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_case_and_whitespace() {
+        assert_eq!(normalize("abaisse"), Ok("ABAISSE".to_string()));
+        assert_eq!(normalize(" AbAiSsE\t\n"), Ok("ABAISSE".to_string()));
+        assert_eq!(normalize(""), Ok("".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_diacritics() {
+        assert_eq!(normalize("élève"), Ok("ELEVE".to_string()));
+        assert_eq!(normalize("ÉLÈVE"), Ok("ELEVE".to_string()));
+        assert_eq!(normalize("garçon"), Ok("GARCON".to_string()));
+        assert_eq!(normalize("Noël"), Ok("NOEL".to_string()));
+        assert_eq!(normalize("naïveté"), Ok("NAIVETE".to_string()));
+        assert_eq!(normalize("pêcheur"), Ok("PECHEUR".to_string()));
+        assert_eq!(normalize("où"), Ok("OU".to_string()));
+        assert_eq!(normalize("ÇA"), Ok("CA".to_string()));
+        // Accents typed as combining marks, after the letter
+        assert_eq!(normalize("e\u{301}le\u{300}ve"), Ok("ELEVE".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_ligatures() {
+        assert_eq!(normalize("cœur"), Ok("COEUR".to_string()));
+        assert_eq!(normalize("ŒUVRE"), Ok("OEUVRE".to_string()));
+        assert_eq!(normalize("ex æquo"), Err(InvalidCharacters(vec![(2, ' ')])));
+        assert_eq!(normalize("tænia"), Ok("TAENIA".to_string()));
+        assert_eq!(normalize("ﬁnal"), Ok("FINAL".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_rejects_non_letters() {
+        assert_eq!(
+            normalize("aujourd'hui"),
+            Err(InvalidCharacters(vec![(7, '\'')]))
+        );
+        assert_eq!(
+            normalize("porte-monnaie"),
+            Err(InvalidCharacters(vec![(5, '-')]))
+        );
+        assert_eq!(
+            normalize("m0t 2"),
+            Err(InvalidCharacters(vec![(1, '0'), (3, ' '), (4, '2')]))
+        );
+        assert_eq!(
+            normalize("αβ"),
+            Err(InvalidCharacters(vec![(0, 'α'), (1, 'β')]))
+        );
+    }
+
+    #[test]
+    fn test_positions_of_invalid_characters_as_typed() {
+        // The leading space counts, the accent typed as a combining mark does not
+        assert_eq!(
+            normalize(" e\u{301}le\u{300}ve-s"),
+            Err(InvalidCharacters(vec![(6, '-')]))
+        );
+    }
+
+    #[test]
+    fn test_invalid_characters_into_app_error() {
+        let error: AppError = normalize("m0t 2").unwrap_err().into();
+        assert!(matches!(
+            error,
+            AppError::InvalidCharacters { positions, chars }
+                if positions == [1, 3, 4] && chars == ['0', ' ', '2']
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_normalize_gives_letters_only(word in "\\PC{0,12}") {
+            if let Ok(normalized) = normalize(&word) {
+                prop_assert!(normalized.chars().all(|c| c.is_ascii_uppercase()));
+            }
+        }
+
+        #[test]
+        fn prop_normalize_is_idempotent(word in "[a-zA-Zàâäçéèêëîïôöùûüÿœæ]{0,12}") {
+            let normalized = normalize(&word).unwrap();
+            prop_assert_eq!(normalize(&normalized), Ok(normalized.clone()));
+        }
+    }
+}
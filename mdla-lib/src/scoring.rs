@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::model::Validation;
+
+/// Score a guess against the word to find, Motus style.
+///
+/// Both words are compared char by char, extra chars of the longest one are ignored.
+pub fn score(word: &str, guess: &str) -> Vec<Validation> {
+    get_validation_list(
+        &word.chars().collect::<Vec<char>>(),
+        &guess.chars().collect::<Vec<char>>(),
+    )
+}
+
+pub fn get_validation_list(word: &[char], guess_word: &[char]) -> Vec<Validation> {
+    let mut validation_list = vec![];
+    let mut chars_not_found = HashMap::new();
+
+    // First pass will correctly set Correct and NotInWord validations but Present validations may be wrong in some cases
+    for (char_word, char_guessed) in Iterator::zip(word.iter(), guess_word.iter()) {
+        let validation = match (char_word == char_guessed, word.contains(char_guessed)) {
+            (true, _) => Validation::Correct(*char_guessed),
+            (false, true) => {
+                let char_count = chars_not_found.entry(char_word).or_insert(0);
+                *char_count += 1;
+                Validation::Present(*char_guessed)
+            }
+            (false, false) => {
+                let char_count = chars_not_found.entry(char_word).or_insert(0);
+                *char_count += 1;
+                Validation::NotInWord(*char_guessed)
+            }
+        };
+
+        validation_list.push(validation);
+    }
+
+    // Second pass will correctly set Present validations based on the correct and present hits set on the previous loop
+    for validation in validation_list.iter_mut() {
+        if let Validation::Present(char_guessed) = *validation {
+            if chars_not_found.get(&char_guessed).unwrap_or(&0) > &0 {
+                let char_count = chars_not_found.get_mut(&char_guessed).expect("No char");
+                *char_count -= 1;
+            } else {
+                *validation = Validation::NotInWord(char_guessed);
+            }
+        }
+    }
+
+    validation_list
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    fn validated_char(v: &Validation) -> char {
+        match v {
+            Validation::Correct(c) | Validation::Present(c) | Validation::NotInWord(c) => *c,
+        }
+    }
+
+    #[test]
+    fn test_get_validation_list_empty() {
+        let result = get_validation_list(&[], &[]);
+        assert_eq!(result, []);
+    }
+
+    #[test]
+    fn test_get_validation_list_all_correct() {
+        let result = get_validation_list(&['v', 'd', 'l', 'a', '!'], &['v', 'd', 'l', 'a']);
+        assert_eq!(
+            result,
+            [
+                Validation::Correct('v'),
+                Validation::Correct('d'),
+                Validation::Correct('l'),
+                Validation::Correct('a'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_validation_list_random() {
+        let mut rng = thread_rng();
+
+        let word_len: usize = rng.gen_range(1..100);
+
+        let mut word = vec!['0'; word_len];
+        let mut guess_word = vec!['0'; word_len];
+
+        rng.fill(&mut word[..]);
+        rng.fill(&mut guess_word[..]);
+
+        let result = get_validation_list(&word, &guess_word);
+
+        assert!(
+            Iterator::zip(result.iter(), guess_word.iter()).all(|(v, c)| validated_char(v) == *c)
+        );
+    }
+
+    #[test]
+    fn test_get_validation_list_mixed_validation() {
+        let result = get_validation_list(&['a', 'b', 'c', 'd', 'e'], &['f', 'a', 'b', 'd', 'g']);
+        assert_eq!(
+            result,
+            [
+                Validation::NotInWord('f'),
+                Validation::Present('a'),
+                Validation::Present('b'),
+                Validation::Correct('d'),
+                Validation::NotInWord('g')
+            ]
+        );
+
+        let result = get_validation_list(&['a', 'b', 'b', 'a'], &['a', 'a', 'b', 'a']);
+        assert_eq!(
+            result,
+            [
+                Validation::Correct('a'),
+                Validation::NotInWord('a'),
+                Validation::Correct('b'),
+                Validation::Correct('a')
+            ]
+        );
+
+        let result = get_validation_list(&['a', 'b', 'b', 'a'], &['b', 'b', 'a', 'b']);
+        assert_eq!(
+            result,
+            [
+                Validation::Present('b'),
+                Validation::Correct('b'),
+                Validation::Present('a'),
+                Validation::NotInWord('b')
+            ]
+        );
+    }
+
+    #[test]
+    fn test_score_duplicate_letters() {
+        assert_eq!(
+            score("ABAISSE", "SSSSSSS"),
+            [
+                Validation::NotInWord('S'),
+                Validation::NotInWord('S'),
+                Validation::NotInWord('S'),
+                Validation::NotInWord('S'),
+                Validation::Correct('S'),
+                Validation::Correct('S'),
+                Validation::NotInWord('S'),
+            ]
+        );
+        assert_eq!(
+            score("ABBAYE", "BABABA"),
+            [
+                Validation::Present('B'),
+                Validation::Present('A'),
+                Validation::Correct('B'),
+                Validation::Correct('A'),
+                Validation::NotInWord('B'),
+                Validation::NotInWord('A'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_score_unicode() {
+        assert_eq!(
+            score("ÉLÈVE", "ÈLÉVE"),
+            [
+                Validation::Present('È'),
+                Validation::Correct('L'),
+                Validation::Present('É'),
+                Validation::Correct('V'),
+                Validation::Correct('E'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_score_length_mismatch() {
+        assert_eq!(score("MDLA", "MDLAXX").len(), 4);
+        assert_eq!(score("MDLAXX", "MDLA").len(), 4);
+        assert_eq!(score("", "MDLA"), []);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_score_keeps_guessed_chars(word in "\\PC{0,12}", guess in "\\PC{0,12}") {
+            let result = score(&word, &guess);
+
+            prop_assert_eq!(result.len(), usize::min(word.chars().count(), guess.chars().count()));
+            prop_assert!(Iterator::zip(result.iter(), guess.chars()).all(|(v, c)| validated_char(v) == c));
+        }
+
+        #[test]
+        fn prop_score_correct_iff_same_char(word in "[A-Z]{1,9}", guess in "[A-Z]{1,9}") {
+            let result = score(&word, &guess);
+
+            for ((v, w), g) in result.iter().zip(word.chars()).zip(guess.chars()) {
+                prop_assert_eq!(matches!(v, Validation::Correct(_)), w == g);
+            }
+        }
+
+        #[test]
+        fn prop_score_never_hints_more_letters_than_the_word_has(word in "[A-E]{1,9}", guess in "[A-E]{1,9}") {
+            let result = score(&word, &guess);
+            let word_prefix: Vec<char> = word.chars().take(result.len()).collect();
+
+            for c in 'A'..='E' {
+                let hinted = result
+                    .iter()
+                    .filter(|v| matches!(v, Validation::Correct(x) | Validation::Present(x) if *x == c))
+                    .count();
+                let in_word = word_prefix.iter().filter(|x| **x == c).count();
+                prop_assert!(hinted <= in_word);
+            }
+        }
+
+        #[test]
+        fn prop_score_same_word_is_all_correct(word in "\\PC{0,12}") {
+            prop_assert!(score(&word, &word).iter().all(|v| matches!(v, Validation::Correct(_))));
+        }
+
+        #[test]
+        fn prop_score_anagram_has_no_missing_letter(word in "[A-Z]{1,9}", seed in any::<u64>()) {
+            let mut guess: Vec<char> = word.chars().collect();
+            let len = guess.len();
+            guess.rotate_left(seed as usize % len);
+
+            let result = get_validation_list(&word.chars().collect::<Vec<char>>(), &guess);
+            prop_assert!(result.iter().all(|v| !matches!(v, Validation::NotInWord(_))));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::model::{GuessResponse, HintsResponse, Validation};
+
+const PICTO_RED: char = '🟥';
+const PICTO_YELLOW: char = '🟡';
+const PICTO_BLUE: char = '🟦';
+
+/// Name of the game, in the title and the score shared.
+pub fn game_label(hints: &HintsResponse) -> String {
+    if hints.challenge.is_some() {
+        "défi".to_string()
+    } else if hints.practice {
+        "entraînement".to_string()
+    } else {
+        format!("#{}", hints.puzzle_number)
+    }
+}
+
+/// Grid of the guesses, one line of emojis per guess, to share a game without giving the word away.
+pub fn share_grid(guesses: &[GuessResponse]) -> Vec<String> {
+    guesses
+        .iter()
+        .map(|guess| {
+            guess
+                .validation_list
+                .iter()
+                .map(|validation| match validation {
+                    Validation::Correct(_) => PICTO_RED,
+                    Validation::Present(_) => PICTO_YELLOW,
+                    Validation::NotInWord(_) => PICTO_BLUE,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Score of a finished game to share: its name, the number of guesses made, and the grid of the guesses.
+pub fn share_text(hints: &HintsResponse, guesses: &[GuessResponse]) -> Vec<String> {
+    let label = game_label(hints);
+    let length = hints
+        .length
+        .map_or(String::new(), |l| format!(" ({l} lettres)"));
+    let mode = if hints.hard { " (difficile)" } else { "" };
+
+    let mut text = vec![format!(
+        "MdlA {label}{length} {}/{}{mode}",
+        guesses.len(),
+        hints.max_attempts
+    )];
+    text.extend(share_grid(guesses));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::GameOutcome, scoring::score};
+
+    fn hints() -> HintsResponse {
+        HintsResponse {
+            game_id: "game".to_string(),
+            puzzle_number: 12,
+            next_rollover: 0,
+            number_of_letters: 7,
+            first_letter: 'A',
+            first_letter_rule: false,
+            hard: false,
+            length: None,
+            practice: false,
+            challenge: None,
+            max_attempts: 6,
+        }
+    }
+
+    fn guesses(word: &str, guesses: &[&str]) -> Vec<GuessResponse> {
+        guesses
+            .iter()
+            .map(|guess| GuessResponse {
+                validation_list: score(word, guess),
+                outcome: GameOutcome::Won,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_share_text() {
+        let guesses = guesses("ABAISSE", &["ACABITS", "ABAISSE"]);
+
+        assert_eq!(
+            share_text(&hints(), &guesses),
+            ["MdlA #12 2/6", "🟥🟦🟥🟡🟡🟦🟡", "🟥🟥🟥🟥🟥🟥🟥"]
+        );
+
+        let hints = HintsResponse {
+            hard: true,
+            length: Some(7),
+            ..hints()
+        };
+        assert_eq!(
+            share_text(&hints, &guesses)[0],
+            "MdlA #12 (7 lettres) 2/6 (difficile)"
+        );
+    }
+
+    #[test]
+    fn test_game_label() {
+        let practice = HintsResponse {
+            practice: true,
+            ..hints()
+        };
+        let challenge = HintsResponse {
+            challenge: Some("abc".to_string()),
+            ..hints()
+        };
+
+        assert_eq!(game_label(&hints()), "#12");
+        assert_eq!(game_label(&practice), "entraînement");
+        assert_eq!(game_label(&challenge), "défi");
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    model::{AppError, Validation},
+    normalize::{normalize_letters, InvalidCharacters},
+    scoring::score,
+};
+
+/// Optional rules of a game.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rules {
+    /// The guesses must start with the first letter of the word, as in Motus
+    pub first_letter: bool,
+    /// The letters found by the previous guesses must be used, Wordle style: the correct ones
+    /// at the same position, the present ones anywhere
+    pub hard: bool,
+}
+
+/// Check a guess against the rules of the game, and return it normalized.
+///
+/// The rules are checked in this order, and the first one broken is returned:
+/// 1. the guess has as many letters as the word to find, each invalid character counting as one
+/// 2. the guess only has letters
+/// 3. the guess starts with the first letter of the word, if the rule is enabled
+/// 4. the guess is in the dictionary
+/// 5. the guess uses the letters found by the previous guesses, in hard mode
+///
+/// Lengths are counted in letters of the normalized words, never in bytes.
+///
+/// The word to find gets no special treatment, so that the answers don't tell it apart from the other words:
+/// it must be in the dictionary to be found.
+pub fn validate_guess(
+    word: &str,
+    guess: &str,
+    dictionary: &HashSet<String>,
+    rules: Rules,
+    past_guesses: &[String],
+) -> Result<String, AppError> {
+    let (normalized, invalid) = normalize_letters(guess);
+
+    let size_expected = word.chars().count();
+    let size_received = normalized.chars().count() + invalid.len();
+    if size_received != size_expected {
+        return Err(AppError::BadWordLength {
+            size_expected,
+            size_received,
+            word_sent: if invalid.is_empty() {
+                normalized
+            } else {
+                guess.trim().to_uppercase()
+            },
+        });
+    }
+
+    if !invalid.is_empty() {
+        return Err(InvalidCharacters(invalid).into());
+    }
+
+    if rules.first_letter {
+        if let (Some(expected), Some(received)) = (word.chars().next(), normalized.chars().next()) {
+            if expected != received {
+                return Err(AppError::WrongFirstLetter { expected, received });
+            }
+        }
+    }
+
+    if !dictionary.contains(&normalized) {
+        return Err(AppError::WordNotInDictionary(normalized));
+    }
+
+    if rules.hard {
+        for past_guess in past_guesses {
+            check_hard_mode(&score(word, past_guess), &normalized)?;
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Check that a guess uses the letters found by a previous guess.
+fn check_hard_mode(past_validations: &[Validation], guess: &str) -> Result<(), AppError> {
+    let guess: Vec<char> = guess.chars().collect();
+
+    let mut found: HashMap<char, usize> = HashMap::new();
+    for (position, validation) in past_validations.iter().enumerate() {
+        match validation {
+            Validation::Correct(letter) => {
+                if guess.get(position) != Some(letter) {
+                    return Err(AppError::HardModeViolation {
+                        letter: *letter,
+                        position: Some(position),
+                    });
+                }
+                *found.entry(*letter).or_default() += 1;
+            }
+            Validation::Present(letter) => *found.entry(*letter).or_default() += 1,
+            Validation::NotInWord(_) => {}
+        }
+    }
+
+    // A letter found twice must be used twice
+    for validation in past_validations {
+        if let Validation::Correct(letter) | Validation::Present(letter) = validation {
+            if guess.iter().filter(|c| *c == letter).count() < found[letter] {
+                return Err(AppError::HardModeViolation {
+                    letter: *letter,
+                    position: None,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictionary() -> HashSet<String> {
+        ["ABAISSE", "ELEVE", "COEUR", "NOEL"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_guess() {
+        assert_eq!(
+            validate_guess("ABAISSE", "abaisse", &dictionary(), Rules::default(), &[]),
+            Ok("ABAISSE".to_string())
+        );
+        // The word to find is checked like any other word, not to give it away
+        assert!(matches!(
+            validate_guess("ABATTRE", "abattre", &dictionary(), Rules::default(), &[]),
+            Err(AppError::WordNotInDictionary(w)) if w == "ABATTRE"
+        ));
+        assert!(matches!(
+            validate_guess("ABAISSE", "ABATTRE", &dictionary(), Rules::default(), &[]),
+            Err(AppError::WordNotInDictionary(w)) if w == "ABATTRE"
+        ));
+    }
+
+    #[test]
+    fn test_validate_guess_multibyte() {
+        // "élève" is 7 bytes long but has 5 letters
+        assert_eq!(
+            validate_guess("ELEVE", "élève", &dictionary(), Rules::default(), &[]),
+            Ok("ELEVE".to_string())
+        );
+        assert_eq!(
+            validate_guess(
+                "ELEVE",
+                "e\u{301}le\u{300}ve",
+                &dictionary(),
+                Rules::default(),
+                &[]
+            ),
+            Ok("ELEVE".to_string())
+        );
+        // "cœur" has 4 characters but 5 letters
+        assert_eq!(
+            validate_guess("COEUR", " Cœur ", &dictionary(), Rules::default(), &[]),
+            Ok("COEUR".to_string())
+        );
+        assert!(matches!(
+            validate_guess("ELEVE", "élèves", &dictionary(), Rules::default(), &[]),
+            Err(AppError::BadWordLength { size_expected: 5, size_received: 6, word_sent })
+                if word_sent == "ELEVES"
+        ));
+        assert!(matches!(
+            validate_guess("ELEVE", "œufs", &dictionary(), Rules::default(), &[]),
+            Err(AppError::WordNotInDictionary(w)) if w == "OEUFS"
+        ));
+    }
+
+    #[test]
+    fn test_validate_guess_errors_order() {
+        // A bad length comes before invalid characters
+        assert!(matches!(
+            validate_guess("ELEVE", "n°1", &dictionary(), Rules::default(), &[]),
+            Err(AppError::BadWordLength { size_expected: 5, size_received: 3, word_sent })
+                if word_sent == "N°1"
+        ));
+        // Invalid characters come before the dictionary
+        assert!(matches!(
+            validate_guess("ELEVE", "él-ve", &dictionary(), Rules::default(), &[]),
+            Err(AppError::InvalidCharacters { positions, chars })
+                if positions == [2] && chars == ['-']
+        ));
+    }
+
+    #[test]
+    fn test_validate_guess_first_letter_rule() {
+        let rules = Rules {
+            first_letter: true,
+            ..Rules::default()
+        };
+
+        assert_eq!(
+            validate_guess("ELEVE", "élève", &dictionary(), rules, &[]),
+            Ok("ELEVE".to_string())
+        );
+        assert_eq!(
+            validate_guess("ELEVE", "cœur", &dictionary(), Rules::default(), &[]),
+            Ok("COEUR".to_string())
+        );
+        assert_eq!(
+            validate_guess("ELEVE", "cœur", &dictionary(), rules, &[]),
+            Err(AppError::WrongFirstLetter {
+                expected: 'E',
+                received: 'C'
+            })
+        );
+        // The first letter comes before the dictionary
+        assert_eq!(
+            validate_guess("COEUR", "œufs", &dictionary(), rules, &[]),
+            Err(AppError::WrongFirstLetter {
+                expected: 'C',
+                received: 'O'
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_guess_hard_mode() {
+        let rules = Rules {
+            hard: true,
+            ..Rules::default()
+        };
+        let dictionary: HashSet<String> = ["ABAISSE", "ABATTRE", "ACABITS", "BAISSEA", "AMAIBSE"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        // Scored against ABAISSE: both A are correct, B, I and S are present
+        let past_guesses = ["ACABITS".to_string()];
+
+        assert_eq!(
+            validate_guess(
+                "ABAISSE",
+                "ABATTRE",
+                &dictionary,
+                Rules::default(),
+                &past_guesses
+            ),
+            Ok("ABATTRE".to_string())
+        );
+        assert_eq!(
+            validate_guess("ABAISSE", "BAISSEA", &dictionary, rules, &past_guesses),
+            Err(AppError::HardModeViolation {
+                letter: 'A',
+                position: Some(0)
+            })
+        );
+        assert_eq!(
+            validate_guess("ABAISSE", "ABATTRE", &dictionary, rules, &past_guesses),
+            Err(AppError::HardModeViolation {
+                letter: 'I',
+                position: None
+            })
+        );
+        assert_eq!(
+            validate_guess("ABAISSE", "AMAIBSE", &dictionary, rules, &past_guesses),
+            Ok("AMAIBSE".to_string())
+        );
+        assert_eq!(
+            validate_guess("ABAISSE", "ABAISSE", &dictionary, rules, &past_guesses),
+            Ok("ABAISSE".to_string())
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use actix_web::{http::header, HttpRequest};
+use sha2::{Digest, Sha256};
+
+/// Token to give as a bearer token in the `Authorization` header to call the admin endpoints.
+#[derive(Debug)]
+pub struct AdminToken(pub String);
+
+impl AdminToken {
+    /// Whether the request gives the token. The digests of the tokens are compared rather than the tokens,
+    /// so that the time taken does not tell how much of the token was right.
+    pub fn is_authorized(&self, req: &HttpRequest) -> bool {
+        let given = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        let same_digest = Sha256::digest(given.unwrap_or_default().as_bytes())
+            == Sha256::digest(self.0.as_bytes());
+        given.is_some() && same_digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn test_is_authorized() {
+        let token = AdminToken("secret".to_string());
+
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .to_http_request();
+        assert!(token.is_authorized(&req));
+
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer nope"))
+            .to_http_request();
+        assert!(!token.is_authorized(&req));
+
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer secre"))
+            .to_http_request();
+        assert!(!token.is_authorized(&req));
+
+        assert!(!token.is_authorized(&TestRequest::default().to_http_request()));
+    }
+}
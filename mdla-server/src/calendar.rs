@@ -0,0 +1,145 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// A game day, from one rollover of the daily word to the next one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameDay {
+    /// Number of days since year 1, used to identify the day in the storage and to pick its word
+    pub day: i64,
+    /// Number of the puzzle shown to the players, the first one being the launch day
+    pub puzzle_number: i64,
+    pub next_rollover: DateTime<Utc>,
+}
+
+/// Tells which game day we are, given the timezone where the daily word rolls over at midnight.
+#[derive(Debug, Clone)]
+pub struct GameCalendar {
+    timezone: Tz,
+    launch_date: NaiveDate,
+}
+
+impl GameCalendar {
+    pub fn new(timezone: Tz, launch_date: NaiveDate) -> Self {
+        Self {
+            timezone,
+            launch_date,
+        }
+    }
+
+    pub fn today(&self) -> GameDay {
+        self.day_at(Utc::now())
+    }
+
+    pub fn day_at(&self, now: DateTime<Utc>) -> GameDay {
+        self.day_of(now.with_timezone(&self.timezone).date_naive())
+    }
+
+    /// Get the day of a puzzle already published, to play it from the archive.
+    /// There is no such day for the puzzles of the future, nor before the launch.
+    pub fn puzzle_day(&self, puzzle_number: i64) -> Option<GameDay> {
+        self.puzzle_day_at(puzzle_number, Utc::now())
+    }
+
+    pub fn puzzle_day_at(&self, puzzle_number: i64, now: DateTime<Utc>) -> Option<GameDay> {
+        if puzzle_number < 1 || puzzle_number > self.day_at(now).puzzle_number {
+            return None;
+        }
+        Some(self.day_of(self.launch_date + Duration::days(puzzle_number - 1)))
+    }
+
+    /// Get the number of the puzzle of a day.
+    pub fn puzzle_number(&self, day: i64) -> i64 {
+        day - days_since_y0(self.launch_date) + 1
+    }
+
+    fn day_of(&self, date: NaiveDate) -> GameDay {
+        let next_date = date + Duration::days(1);
+        let next_midnight = next_date.and_hms_opt(0, 0, 0).expect("Midnight exists");
+
+        // Midnight may not exist in the timezone when switching to summer time, the rollover happens as soon as possible then
+        let next_rollover = self
+            .timezone
+            .from_local_datetime(&next_midnight)
+            .earliest()
+            .unwrap_or_else(|| {
+                self.timezone
+                    .from_local_datetime(&(next_midnight + Duration::hours(1)))
+                    .earliest()
+                    .expect("1 AM exists")
+            })
+            .with_timezone(&Utc);
+
+        GameDay {
+            day: days_since_y0(date),
+            puzzle_number: self.puzzle_number(days_since_y0(date)),
+            next_rollover,
+        }
+    }
+}
+
+fn days_since_y0(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar() -> GameCalendar {
+        GameCalendar::new(
+            chrono_tz::Europe::Paris,
+            NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(),
+        )
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_day_at_launch() {
+        let day = calendar().day_at(utc(2022, 3, 1, 12, 0));
+        assert_eq!(day.puzzle_number, 1);
+        assert_eq!(day.next_rollover, utc(2022, 3, 1, 23, 0));
+    }
+
+    #[test]
+    fn test_day_rolls_over_at_local_midnight() {
+        let before = calendar().day_at(utc(2022, 7, 14, 21, 59));
+        let after = calendar().day_at(utc(2022, 7, 14, 22, 0));
+
+        assert_eq!(before.puzzle_number, 136);
+        assert_eq!(after.puzzle_number, 137);
+        assert_eq!(after.day, before.day + 1);
+        assert_eq!(before.next_rollover, utc(2022, 7, 14, 22, 0));
+        assert_eq!(after.next_rollover, utc(2022, 7, 15, 22, 0));
+    }
+
+    #[test]
+    fn test_puzzle_day() {
+        let now = utc(2022, 7, 14, 12, 0);
+        let today = calendar().day_at(now);
+
+        let first = calendar().puzzle_day_at(1, now).unwrap();
+        assert_eq!(first, calendar().day_at(utc(2022, 3, 1, 12, 0)));
+        assert_eq!(calendar().puzzle_number(first.day), 1);
+        assert_eq!(
+            calendar().puzzle_day_at(today.puzzle_number, now),
+            Some(today.clone())
+        );
+
+        assert_eq!(calendar().puzzle_day_at(0, now), None);
+        assert_eq!(calendar().puzzle_day_at(today.puzzle_number + 1, now), None);
+    }
+
+    #[test]
+    fn test_day_matches_former_utc_seed() {
+        let now = utc(2022, 7, 14, 12, 0);
+        let days_since_y0 = (now - utc(1, 1, 1, 0, 0)).num_days();
+
+        let utc_calendar =
+            GameCalendar::new(chrono_tz::UTC, NaiveDate::from_ymd_opt(2022, 3, 1).unwrap());
+        assert_eq!(utc_calendar.day_at(now).day, days_since_y0);
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use log::{debug, error, warn};
+
+use crate::{
+    calendar::GameDay,
+    dictionary::Dictionaries,
+    schedule::{extend_schedule, Schedule, DAYS_AHEAD, DAYS_AHEAD_MIN},
+    storage::{Storage, StorageError},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+struct CachedWord {
+    day: i64,
+    word: String,
+}
+
+/// What the schedules need to be extended while the server runs.
+#[derive(Debug)]
+pub struct ScheduleExtension {
+    /// File of the schedule of the classic puzzles, next to the ones of each length
+    pub file: String,
+    pub secret: String,
+    pub dictionaries: Arc<Dictionaries>,
+}
+
+/// Words of the puzzles, from the schedule the first time a day is asked for.
+/// A word is then saved in the storage, so it never changes afterwards.
+///
+/// Each length of words has its own puzzles and schedule, the classic puzzles (`None`) mixing all the lengths.
+///
+/// Today's words are asked for on every request, so they are kept in memory until the next game day
+/// is asked for, which replaces them at once. That is also when a schedule is extended, if it is
+/// about to run out.
+#[derive(Debug)]
+pub struct DailyWords {
+    storage: Arc<dyn Storage>,
+    schedules: RwLock<HashMap<Option<usize>, Schedule>>,
+    extension: Option<ScheduleExtension>,
+    latest: RwLock<HashMap<Option<usize>, CachedWord>>,
+}
+
+impl DailyWords {
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        schedules: HashMap<Option<usize>, Schedule>,
+        extension: Option<ScheduleExtension>,
+    ) -> Self {
+        Self {
+            storage,
+            schedules: RwLock::new(schedules),
+            extension,
+            latest: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Whether there are puzzles with words of this length.
+    pub fn has_length(&self, length: Option<usize>) -> bool {
+        self.schedules
+            .read()
+            .expect("Read schedules")
+            .contains_key(&length)
+    }
+
+    /// Get the word of a game day for the given length, none if it has not been scheduled.
+    pub fn word(
+        &self,
+        day: &GameDay,
+        length: Option<usize>,
+    ) -> Result<Option<String>, StorageError> {
+        let latest_day = match self.latest.read().expect("Read daily word").get(&length) {
+            Some(cached) if cached.day == day.day => return Ok(Some(cached.word.clone())),
+            Some(cached) => Some(cached.day),
+            None => None,
+        };
+
+        // Only a newer day replaces the cached word, the older ones are puzzles of the archive
+        let rollover = !matches!(latest_day, Some(latest) if latest >= day.day);
+        if rollover {
+            self.extend_ahead(day, length);
+        }
+
+        let word = match self.load(day, length)? {
+            Some(word) => word,
+            None => return Ok(None),
+        };
+
+        if rollover {
+            let mut latest = self.latest.write().expect("Write daily word");
+            if !matches!(latest.get(&length), Some(cached) if cached.day >= day.day) {
+                latest.insert(
+                    length,
+                    CachedWord {
+                        day: day.day,
+                        word: word.clone(),
+                    },
+                );
+            }
+        }
+        Ok(Some(word))
+    }
+
+    fn load(&self, day: &GameDay, length: Option<usize>) -> Result<Option<String>, StorageError> {
+        if let Some(word) = self.storage.get_daily_word(day.day, length)? {
+            return Ok(Some(word));
+        }
+
+        let puzzle_number = day.puzzle_number;
+        let word = match self
+            .schedules
+            .read()
+            .expect("Read schedules")
+            .get(&length)
+            .and_then(|schedule| schedule.word(puzzle_number))
+        {
+            Some(word) => word.to_string(),
+            None => return Ok(None),
+        };
+
+        debug!("Word of puzzle {puzzle_number} ({length:?} letters) is : {word:?}");
+        self.storage.save_daily_word(day.day, length, &word)?;
+        Ok(Some(word))
+    }
+
+    /// Extend the schedule of the length if fewer than [`DAYS_AHEAD_MIN`] puzzles are left after the day.
+    fn extend_ahead(&self, day: &GameDay, length: Option<usize>) {
+        let extension = match &self.extension {
+            Some(extension) => extension,
+            None => return,
+        };
+        let until = day.puzzle_number + DAYS_AHEAD_MIN;
+        let running_out = |schedules: &HashMap<Option<usize>, Schedule>| matches!(schedules.get(&length), Some(schedule) if schedule.len() < until);
+        if !running_out(&self.schedules.read().expect("Read schedules")) {
+            return;
+        }
+
+        let mut schedules = self.schedules.write().expect("Write schedules");
+        // Another request may have extended it meanwhile
+        if !running_out(&schedules) {
+            return;
+        }
+        let schedule = schedules.get_mut(&length).expect("Schedule of the length");
+        let scheduled = schedule.words().len();
+        let file = Schedule::file_for_length(&extension.file, length);
+        warn!("Less than {DAYS_AHEAD_MIN} puzzles left in {file}, extending the schedule");
+        if let Err(e) = extend_schedule(
+            schedule,
+            &file,
+            &extension.dictionaries.get().playable_word_list,
+            length,
+            day.puzzle_number + DAYS_AHEAD,
+            &extension.secret,
+        ) {
+            error!("Can't extend the schedule {file}: {e}");
+        }
+        extension
+            .dictionaries
+            .keep(schedule.words()[scheduled..].iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::{storage::memory::MemoryStorage, test_files::TestFile};
+
+    use super::*;
+
+    fn game_day(puzzle_number: i64) -> GameDay {
+        GameDay {
+            day: 1000 + puzzle_number,
+            puzzle_number,
+            next_rollover: Utc::now(),
+        }
+    }
+
+    fn daily_words(storage: Arc<dyn Storage>) -> DailyWords {
+        let mut schedule = Schedule::default();
+        schedule.extend(
+            &["ABAISSE", "ABATTRE", "ABBAYE"].map(String::from),
+            3,
+            "secret",
+        );
+        let mut schedule_6 = Schedule::default();
+        schedule_6.extend(&["ABBAYE", "ABOLIR"].map(String::from), 3, "secret");
+        DailyWords::new(
+            storage,
+            HashMap::from([(None, schedule), (Some(6), schedule_6)]),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_word_is_saved_and_cached() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+        let daily_words = daily_words(storage.clone());
+
+        let word = daily_words.word(&game_day(2), None).unwrap().unwrap();
+        assert_eq!(
+            storage.get_daily_word(1002, None).unwrap(),
+            Some(word.clone())
+        );
+        assert_eq!(
+            daily_words.word(&game_day(2), None).unwrap(),
+            Some(word.clone())
+        );
+        assert_eq!(
+            daily_words.latest.read().unwrap().get(&None),
+            Some(&CachedWord { day: 1002, word })
+        );
+    }
+
+    #[test]
+    fn test_rollover_replaces_cached_word() {
+        let daily_words = daily_words(Arc::new(MemoryStorage::default()));
+
+        let word_2 = daily_words.word(&game_day(2), None).unwrap();
+        let word_3 = daily_words.word(&game_day(3), None).unwrap();
+        assert_ne!(word_2, word_3);
+        assert_eq!(daily_words.latest.read().unwrap()[&None].day, 1003);
+
+        // Puzzles of the archive don't replace today's word
+        assert_eq!(daily_words.word(&game_day(2), None).unwrap(), word_2);
+        assert_eq!(daily_words.latest.read().unwrap()[&None].day, 1003);
+    }
+
+    #[test]
+    fn test_rollover_extends_schedule_running_out() {
+        let all = TestFile::with_content("all", "ABAISSE\nABATTRE\nABBAYE\n");
+        let playable = TestFile::with_content("playable", "ABAISSE\nABATTRE\nABBAYE\n");
+        let schedule_file = TestFile::new("schedule.db");
+        let extension = ScheduleExtension {
+            file: schedule_file.path().to_string(),
+            secret: "secret".to_string(),
+            dictionaries: Arc::new(Dictionaries::load(all.path(), playable.path()).unwrap()),
+        };
+        let daily_words = DailyWords {
+            extension: Some(extension),
+            ..daily_words(Arc::new(MemoryStorage::default()))
+        };
+
+        assert!(daily_words.word(&game_day(2), None).unwrap().is_some());
+        assert_eq!(
+            daily_words.schedules.read().unwrap()[&None].len(),
+            2 + DAYS_AHEAD
+        );
+        assert_eq!(
+            Schedule::load(schedule_file.path(), "secret")
+                .unwrap()
+                .len(),
+            2 + DAYS_AHEAD - 3
+        );
+        // The schedules of the other lengths are only extended when they are played
+        assert_eq!(daily_words.schedules.read().unwrap()[&Some(6)].len(), 3);
+        assert!(daily_words.word(&game_day(40), None).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_word_not_scheduled() {
+        let daily_words = daily_words(Arc::new(MemoryStorage::default()));
+
+        assert_eq!(daily_words.word(&game_day(4), None).unwrap(), None);
+        assert_eq!(daily_words.word(&game_day(1), Some(7)).unwrap(), None);
+        assert!(daily_words.latest.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_word_of_each_length() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+        let daily_words = daily_words(storage.clone());
+
+        let classic = daily_words.word(&game_day(1), None).unwrap().unwrap();
+        let word_6 = daily_words.word(&game_day(1), Some(6)).unwrap().unwrap();
+        assert_eq!(word_6.len(), 6);
+        assert_eq!(storage.get_daily_word(1001, None).unwrap(), Some(classic));
+        assert_eq!(storage.get_daily_word(1001, Some(6)).unwrap(), Some(word_6));
+        assert!(daily_words.has_length(Some(6)));
+        assert!(!daily_words.has_length(Some(7)));
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, ErrorKind},
+};
+
+use log::info;
+use mdla_lib::normalize::normalize;
+
+use crate::dictionary::DictionaryError;
+
+/// Definitions of the words, shown with the answer once a game is over.
+///
+/// They are read from a tab separated file, with one word and its definition per line, or from a JSON file
+/// (`.json`) with an object of the definitions by word. The file is optional: without it, the words just have
+/// no definition.
+#[derive(Debug, Default)]
+pub struct Definitions {
+    definitions: HashMap<String, String>,
+}
+
+impl Definitions {
+    pub fn load(file: &str) -> Result<Self, DictionaryError> {
+        let f = match File::open(file) {
+            Ok(f) => f,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                info!("No definitions file {file}, the answers will have no definition");
+                return Ok(Self::default());
+            }
+            Err(e) => {
+                return Err(DictionaryError(format!(
+                    "Can't open definitions file {file}: {e}"
+                )))
+            }
+        };
+
+        let definitions = if file.ends_with(".json") {
+            read_json(f, file)?
+        } else {
+            read_tsv(f, file)?
+        };
+
+        info!("{} definitions loaded from file {file}", definitions.len());
+        Ok(Self { definitions })
+    }
+
+    /// Get the definition of a normalized word, if there is one.
+    pub fn get(&self, word: &str) -> Option<&str> {
+        self.definitions.get(word).map(String::as_str)
+    }
+
+    /// Words without a definition, in the order given.
+    pub fn missing<'a>(&self, words: &'a [String]) -> Vec<&'a String> {
+        words
+            .iter()
+            .filter(|w| !self.definitions.contains_key(*w))
+            .collect()
+    }
+}
+
+fn read_tsv(f: File, file: &str) -> Result<HashMap<String, String>, DictionaryError> {
+    let mut definitions = HashMap::new();
+    for (i, line) in BufReader::new(f).lines().enumerate() {
+        let line =
+            line.map_err(|e| DictionaryError(format!("Can't read definitions file {file}: {e}")))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid_line =
+            || DictionaryError(format!("Invalid line {} of file {file}: {line:?}", i + 1));
+
+        let (word, definition) = line.split_once('\t').ok_or_else(invalid_line)?;
+        let word = normalize(word).map_err(|_| invalid_line())?;
+        if word.is_empty() || definition.trim().is_empty() {
+            return Err(invalid_line());
+        }
+        definitions.insert(word, definition.trim().to_string());
+    }
+    Ok(definitions)
+}
+
+fn read_json(f: File, file: &str) -> Result<HashMap<String, String>, DictionaryError> {
+    let by_word: HashMap<String, String> = serde_json::from_reader(BufReader::new(f))
+        .map_err(|e| DictionaryError(format!("Can't read definitions file {file}: {e}")))?;
+
+    by_word
+        .into_iter()
+        .map(|(word, definition)| match normalize(&word) {
+            Ok(normalized) if !normalized.is_empty() && !definition.trim().is_empty() => {
+                Ok((normalized, definition.trim().to_string()))
+            }
+            _ => Err(DictionaryError(format!(
+                "Invalid definition of {word:?} in file {file}"
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_files::TestFile;
+
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let file = TestFile::with_content(
+            "definitions.tsv",
+            "abaisse\tPâte amincie au rouleau.\n\nÉlève\tPersonne qui reçoit un enseignement. \n",
+        );
+        let definitions = Definitions::load(file.path()).unwrap();
+
+        assert_eq!(definitions.get("ABAISSE"), Some("Pâte amincie au rouleau."));
+        assert_eq!(
+            definitions.get("ELEVE"),
+            Some("Personne qui reçoit un enseignement.")
+        );
+        assert_eq!(definitions.get("ABATTRE"), None);
+    }
+
+    #[test]
+    fn test_load_json() {
+        let file = TestFile::with_content(
+            "definitions.json",
+            r#"{"abaisse": "Pâte amincie au rouleau.", "Élève": "Personne qui reçoit un enseignement."}"#,
+        );
+        let definitions = Definitions::load(file.path()).unwrap();
+
+        assert_eq!(definitions.get("ABAISSE"), Some("Pâte amincie au rouleau."));
+        assert_eq!(
+            definitions.get("ELEVE"),
+            Some("Personne qui reçoit un enseignement.")
+        );
+
+        let file = TestFile::with_content("invalid-definitions.json", r#"["ABAISSE"]"#);
+        assert!(Definitions::load(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_missing() {
+        let file = TestFile::with_content("missing-definitions.tsv", "ABAISSE\tPâte amincie.\n");
+        let definitions = Definitions::load(file.path()).unwrap();
+        let words = ["ABATTRE", "ABAISSE", "ABBAYE"].map(String::from);
+
+        assert_eq!(definitions.missing(&words), [&words[0], &words[2]]);
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let file = TestFile::with_content("invalid-definitions.tsv", "ABAISSE Pâte amincie\n");
+        assert!(Definitions::load(file.path()).is_err());
+        let file = TestFile::with_content("empty-definition.tsv", "ABAISSE\t \n");
+        assert!(Definitions::load(file.path()).is_err());
+
+        assert!(Definitions::load("./no-such-file.tsv")
+            .unwrap()
+            .get("ABAISSE")
+            .is_none());
+    }
+}
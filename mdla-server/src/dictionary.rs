@@ -0,0 +1,218 @@
+use std::{
+    collections::HashSet,
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader},
+    sync::{Arc, RwLock},
+};
+
+use log::{info, warn};
+use mdla_lib::{model::AppState, normalize::normalize};
+
+#[derive(Debug)]
+pub struct DictionaryError(pub String);
+
+impl Display for DictionaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Dictionary error: {}", self.0)
+    }
+}
+
+impl std::error::Error for DictionaryError {}
+
+/// The word lists, loaded from their files at startup and reloaded on demand.
+///
+/// A reload swaps both lists at once, and only if they are valid: requests see either the old
+/// lists or the new ones.
+///
+/// The playable words are always in the dictionary, so that they can be found. So are the words
+/// kept, those of the puzzles and challenges: they come from the schedule and the storage, so a
+/// reload never changes them, and they must stay possible to find even if removed from the lists.
+#[derive(Debug)]
+pub struct Dictionaries {
+    all_words_file: String,
+    playable_words_file: String,
+    kept_words: RwLock<HashSet<String>>,
+    current: RwLock<Arc<AppState>>,
+}
+
+impl Dictionaries {
+    pub fn load(all_words_file: &str, playable_words_file: &str) -> Result<Self, DictionaryError> {
+        let current = load_app_state(all_words_file, playable_words_file, &HashSet::new())?;
+        Ok(Self {
+            all_words_file: all_words_file.to_string(),
+            playable_words_file: playable_words_file.to_string(),
+            kept_words: RwLock::new(HashSet::new()),
+            current: RwLock::new(Arc::new(current)),
+        })
+    }
+
+    /// Keep words in the dictionary from now on, even once removed from the files.
+    pub fn keep(&self, words: impl IntoIterator<Item = String>) {
+        let mut kept_words = self.kept_words.write().expect("Write kept words");
+        let mut current = self.current.write().expect("Write dictionaries");
+        let missing: Vec<String> = words
+            .into_iter()
+            .filter(|word| kept_words.insert(word.clone()))
+            .filter(|word| !current.all_word_list.contains(word))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+
+        let mut all_word_list = current.all_word_list.clone();
+        all_word_list.extend(missing);
+        *current = Arc::new(AppState {
+            all_word_list,
+            playable_word_list: current.playable_word_list.clone(),
+        });
+    }
+
+    /// Get the current word lists. They stay the same for the caller even if they are reloaded meanwhile.
+    pub fn get(&self) -> Arc<AppState> {
+        self.current.read().expect("Read dictionaries").clone()
+    }
+
+    /// Read the files again and swap the word lists if they are valid, keeping the current ones otherwise.
+    pub fn reload(&self) -> Result<Arc<AppState>, DictionaryError> {
+        let kept_words = self.kept_words.read().expect("Read kept words");
+        let new = Arc::new(load_app_state(
+            &self.all_words_file,
+            &self.playable_words_file,
+            &kept_words,
+        )?);
+        *self.current.write().expect("Write dictionaries") = new.clone();
+        info!("Dictionaries reloaded");
+        Ok(new)
+    }
+}
+
+fn load_app_state(
+    all_words_file: &str,
+    playable_words_file: &str,
+    kept_words: &HashSet<String>,
+) -> Result<AppState, DictionaryError> {
+    let mut app_state = AppState {
+        all_word_list: get_words(all_words_file)?.into_iter().collect(),
+        playable_word_list: get_words(playable_words_file)?,
+    };
+
+    let not_in_dictionary = app_state
+        .playable_word_list
+        .iter()
+        .filter(|w| app_state.all_word_list.insert(w.to_string()))
+        .count();
+    if not_in_dictionary > 0 {
+        warn!("{not_in_dictionary} playable words are not in the dictionary {all_words_file}, they are added to it");
+    }
+    let removed = kept_words
+        .iter()
+        .filter(|w| app_state.all_word_list.insert(w.to_string()))
+        .count();
+    if removed > 0 {
+        warn!("{removed} words of the puzzles are no longer in the dictionary {all_words_file}, they are kept in it");
+    }
+    Ok(app_state)
+}
+
+/// Read a words file, with one word per line, and normalize its words.
+/// The file must have words, and no blank line nor word with other characters than letters.
+fn get_words(file: &str) -> Result<Vec<String>, DictionaryError> {
+    let file_words = File::open(file)
+        .map_err(|e| DictionaryError(format!("Can't open words file {file}: {e}")))?;
+
+    let words = BufReader::new(file_words)
+        .lines()
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| DictionaryError(format!("Can't read words file {file}: {e}")))?;
+
+    if words.is_empty() {
+        return Err(DictionaryError(format!("No word in file {file}")));
+    }
+    let words = words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| match normalize(word) {
+            Ok(normalized) if !normalized.is_empty() => Ok(normalized),
+            _ => Err(DictionaryError(format!(
+                "Invalid word {word:?} at line {} of file {file}",
+                i + 1
+            ))),
+        })
+        .collect::<Result<Vec<String>, _>>()?;
+
+    let nb_words = words.len();
+    info!("{nb_words} words loaded from file {file}");
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::test_files::TestFile;
+
+    use super::*;
+
+    #[test]
+    fn test_reload() {
+        let all = TestFile::with_content("reload-all", "ABAISSE\nABATTRE\n");
+        let playable = TestFile::with_content("reload-playable", "ABAISSE\n");
+        let dictionaries = Dictionaries::load(all.path(), playable.path()).unwrap();
+        let before = dictionaries.get();
+
+        all.write("ABAISSE\nABATTRE\nABBAYE\nélève \n");
+        dictionaries.reload().unwrap();
+
+        assert!(dictionaries.get().all_word_list.contains("ABBAYE"));
+        assert!(dictionaries.get().all_word_list.contains("ELEVE"));
+        assert!(!before.all_word_list.contains("ABBAYE"));
+    }
+
+    #[test]
+    fn test_kept_words_stay_after_reload() {
+        let all = TestFile::with_content("kept-all", "ABAISSE\nABATTRE\n");
+        let playable = TestFile::with_content("kept-playable", "ABATTRE\n");
+        let dictionaries = Dictionaries::load(all.path(), playable.path()).unwrap();
+        dictionaries.keep(["ABATTRE".to_string(), "ABBAYE".to_string()]);
+        assert!(dictionaries.get().all_word_list.contains("ABBAYE"));
+
+        // Today's word stays the same, so it must stay possible to find
+        all.write("ABAISSE\n");
+        playable.write("ABAISSE\n");
+        dictionaries.reload().unwrap();
+        assert!(dictionaries.get().all_word_list.contains("ABATTRE"));
+        assert!(dictionaries.get().all_word_list.contains("ABBAYE"));
+        assert_eq!(dictionaries.get().playable_word_list, ["ABAISSE"]);
+    }
+
+    #[test]
+    fn test_reload_invalid_keeps_current_lists() {
+        let all = TestFile::with_content("invalid-all", "ABAISSE\nABATTRE\n");
+        let playable = TestFile::with_content("invalid-playable", "ABAISSE\n");
+        let dictionaries = Dictionaries::load(all.path(), playable.path()).unwrap();
+
+        all.write("ABAISSE\n\nABBAYE\n");
+        assert!(dictionaries.reload().is_err());
+        all.write("ABAISSE\nPORTE-MONNAIE\n");
+        assert!(dictionaries.reload().is_err());
+        all.write("");
+        assert!(dictionaries.reload().is_err());
+        fs::remove_file(playable.path()).unwrap();
+        assert!(dictionaries.reload().is_err());
+
+        assert_eq!(dictionaries.get().all_word_list.len(), 2);
+        assert_eq!(dictionaries.get().playable_word_list, ["ABAISSE"]);
+    }
+
+    #[test]
+    fn test_playable_words_are_in_dictionary() {
+        let all = TestFile::with_content("superset-all", "ABAISSE\nABATTRE\n");
+        let playable = TestFile::with_content("superset-playable", "ABAISSE\nABBAYE\n");
+        let dictionaries = Dictionaries::load(all.path(), playable.path()).unwrap();
+
+        assert!(dictionaries.get().all_word_list.contains("ABBAYE"));
+        assert_eq!(dictionaries.get().all_word_list.len(), 3);
+    }
+}
@@ -1,210 +1,478 @@
-use std::collections::HashMap;
-
 use actix_web::{
     get, post,
-    web::{Data, Json},
-    Result,
+    web::{Data, Json, Path, Query},
+    HttpRequest, HttpResponse, Result,
+};
+
+use log::{debug, error, warn};
+
+use mdla_lib::{
+    error::ApiError,
+    model::{
+        AnswerResponse, AppError, ArchiveResponse, ChallengeBody, ChallengeResponse,
+        DefinitionResponse, GameQuery, GameResponse, GuessBody, GuessResponse, HintsQuery,
+        HintsResponse, ModeQuery, PracticeQuery, ReloadResponse, ResponseOrError, StatsResponse,
+        Validation,
+    },
+    normalize::normalize,
+    scoring::get_validation_list,
+    validation::validate_guess,
 };
-use chrono::{TimeZone, Utc};
 
-use log::{info, warn};
-use rand::{
-    prelude::{IteratorRandom, StdRng},
-    SeedableRng,
+use rand::seq::SliceRandom;
+
+use crate::{
+    admin::AdminToken,
+    calendar::{GameCalendar, GameDay},
+    daily_word::DailyWords,
+    definitions::Definitions,
+    dictionary::Dictionaries,
+    errors::api_error,
+    openapi,
+    player::{get_or_create_player_id, get_player_id},
+    session::{Session, Sessions},
+    statistics::{compute_stats, list_archive},
+    storage::Storage,
 };
 
-use mdla_lib::model::{AppError, AppState, GuessBody, GuessResponse, HintsResponse, Validation};
+fn get_word(daily_words: &DailyWords, day: &GameDay, length: Option<usize>) -> Result<String> {
+    match daily_words.word(day, length)? {
+        Some(word) => Ok(word),
+        None => {
+            let puzzle_number = day.puzzle_number;
+            error!("No word scheduled for puzzle {puzzle_number} ({length:?} letters), the schedule must be extended");
+            Err(api_error(ApiError::Internal("No word today".to_string())))
+        }
+    }
+}
+
+/// Get the day of the puzzle asked for: today's one by default, or a past one from the archive.
+fn get_game_day(calendar: &GameCalendar, puzzle: Option<i64>) -> Result<GameDay> {
+    match puzzle {
+        None => Ok(calendar.today()),
+        Some(n) => calendar
+            .puzzle_day(n)
+            .ok_or_else(|| app_error(AppError::PuzzleNotAvailable(n))),
+    }
+}
 
-use crate::errors::ResponseOrError;
+/// Check that there are puzzles with words of the length asked for.
+fn check_length(daily_words: &DailyWords, length: Option<usize>) -> Result<()> {
+    match length {
+        Some(length) if !daily_words.has_length(Some(length)) => {
+            Err(app_error(AppError::LengthNotAvailable(length)))
+        }
+        _ => Ok(()),
+    }
+}
 
-fn get_today_word(words: &[String]) -> String {
-    // The goal here is to get a number that change everyday in order to initialise the seed of the random number generator.
-    let days_since_y0 = (Utc::now() - Utc.ymd(1, 1, 1).and_hms(0, 0, 0)).num_days();
-    info!("Seed init to: {days_since_y0}");
+/// What a game is played on, as asked for by the player.
+enum GameKind<'a> {
+    /// Today's puzzle or one from the archive, of the given length of words
+    Puzzle {
+        puzzle: Option<i64>,
+        length: Option<usize>,
+    },
+    Practice,
+    Challenge(&'a str),
+}
 
-    let mut rng: StdRng = SeedableRng::seed_from_u64(days_since_y0.unsigned_abs());
+impl<'a> GameKind<'a> {
+    fn new(
+        puzzle: Option<i64>,
+        length: Option<usize>,
+        practice_game: bool,
+        challenge: &'a Option<String>,
+    ) -> Self {
+        match (challenge, practice_game) {
+            (Some(challenge), _) => GameKind::Challenge(challenge),
+            (None, true) => GameKind::Practice,
+            (None, false) => GameKind::Puzzle { puzzle, length },
+        }
+    }
+}
 
-    let word = words
-        .iter()
-        .choose(&mut rng)
-        .expect("Choose a word...")
-        .clone();
+/// Get a game with the word to find and the day it is played: the day of its puzzle,
+/// or today for a practice game or a challenge.
+fn get_game(
+    sessions: &Sessions,
+    daily_words: &DailyWords,
+    calendar: &GameCalendar,
+    game_id: &str,
+    kind: GameKind,
+) -> Result<(Session, GameDay, String)> {
+    let game_not_found = || app_error(AppError::GameNotFound(game_id.to_string()));
+
+    let (puzzle, length) = match kind {
+        GameKind::Puzzle { puzzle, length } => (puzzle, length),
+        GameKind::Practice | GameKind::Challenge(_) => {
+            let session = match kind {
+                GameKind::Challenge(challenge) => sessions.get_challenge(game_id, challenge)?,
+                _ => sessions.get_practice(game_id)?,
+            }
+            .ok_or_else(game_not_found)?;
+            let word = session.word.clone().unwrap_or_default();
+            return Ok((session, calendar.today(), word));
+        }
+    };
 
-    info!("Today word is : {word:?}");
-    word
+    check_length(daily_words, length)?;
+    let day = get_game_day(calendar, puzzle)?;
+    let session = sessions
+        .get(game_id, day.day, length)?
+        .ok_or_else(game_not_found)?;
+    let word = get_word(daily_words, &day, length)?.to_uppercase();
+    Ok((session, day, word))
+}
+
+/// Log an error made by the player and turn it into a response.
+fn app_error(error: AppError) -> actix_web::Error {
+    warn!("{error:?}");
+    api_error(error.into())
 }
 
 #[post("/guess")]
 pub async fn guess(
-    data: Data<AppState>,
+    dictionaries: Data<Dictionaries>,
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
     guess_body: Json<GuessBody>,
 ) -> Result<Json<ResponseOrError<GuessResponse>>> {
-    info!("Body : {guess_body:?}");
+    debug!("Body : {guess_body:?}");
+
+    let (session, _, day_word) = get_game(
+        &sessions,
+        &daily_words,
+        &calendar,
+        &guess_body.game_id,
+        GameKind::new(
+            guess_body.puzzle,
+            guess_body.length,
+            guess_body.practice,
+            &guess_body.challenge,
+        ),
+    )?;
+    if session.is_finished() {
+        return Err(app_error(AppError::GameFinished));
+    }
 
-    let word: Vec<char> = get_today_word(&data.playable_word_list)
-        .to_uppercase()
-        .chars()
-        .collect();
+    let word: Vec<char> = day_word.chars().collect();
 
-    let guess = guess_body.guess.to_uppercase();
+    let guess = validate_guess(
+        &day_word,
+        &guess_body.guess,
+        &dictionaries.get().all_word_list,
+        sessions.rules(&session),
+        &session.guesses,
+    )
+    .map_err(app_error)?;
     let guess_vec: Vec<char> = guess.chars().collect();
 
-    if !data.all_word_list.contains(&guess) && guess_vec != word {
-        let error = AppError::WordNotInDictionary(guess);
-        warn!("{error:?}");
-        return Err(ResponseOrError::<GuessResponse>::Error(error).into());
-    }
-    if word.len() != guess.len() {
-        let error = AppError::BadWordLength {
-            size_expected: word.len(),
-            size_received: guess.len(),
-            word_sent: guess,
-        };
-        warn!("{error:?}");
-        return Err(ResponseOrError::<GuessResponse>::Error(error).into());
-    }
-
-    let validation_list = get_validation_list(word, guess_vec);
-
-    let response = GuessResponse { validation_list };
+    let validation_list = get_validation_list(&word, &guess_vec);
+    let found = validation_list
+        .iter()
+        .all(|v| matches!(v, Validation::Correct(_)));
+    let outcome = sessions
+        .record_guess(&session, &day_word, &guess, found)?
+        .ok_or_else(|| app_error(AppError::GameFinished))?;
+
+    let response = GuessResponse {
+        validation_list,
+        outcome,
+    };
     Ok(Json(ResponseOrError::Response(response)))
 }
 
 #[get("/hints")]
-pub async fn hints(data: Data<AppState>) -> Result<Json<HintsResponse>> {
-    let word: Vec<char> = get_today_word(&data.playable_word_list).chars().collect();
-
-    let response = HintsResponse {
-        first_letter: word[0],
-        number_of_letters: word.len(),
+pub async fn hints(
+    req: HttpRequest,
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    query: Query<HintsQuery>,
+) -> Result<HttpResponse> {
+    let (player_id, player_cookie) = get_or_create_player_id(&req);
+    let game_id = match &query.challenge {
+        Some(challenge) => sessions
+            .start_challenge(&player_id, calendar.today().day, query.hard, challenge)?
+            .ok_or_else(|| app_error(AppError::ChallengeNotFound(challenge.clone())))?,
+        None => {
+            check_length(&daily_words, query.length)?;
+            let day = get_game_day(&calendar, query.puzzle)?;
+            // Make sure the puzzle has a word before starting a game
+            get_word(&daily_words, &day, query.length)?;
+            let from_archive = day.day < calendar.today().day;
+            sessions.start(&player_id, day.day, query.length, query.hard, from_archive)?
+        }
     };
-    Ok(Json(response))
-}
+    let kind = GameKind::new(query.puzzle, query.length, false, &query.challenge);
+    let (session, day, word) = get_game(&sessions, &daily_words, &calendar, &game_id, kind)?;
 
-fn get_validation_list(word: Vec<char>, guess_word: Vec<char>) -> Vec<Validation> {
-    let mut validation_list = vec![];
-    let mut chars_not_found = HashMap::new();
+    let mut response = HttpResponse::Ok();
+    if let Some(cookie) = player_cookie {
+        response.cookie(cookie);
+    }
+    Ok(response.json(ResponseOrError::Response(get_hints(
+        &word, &session, &day, &sessions,
+    ))))
+}
 
-    // First pass will correctly set Correct and NotInWord validations but Present validations may be wrong in some cases
-    for (char_word, char_guessed) in Iterator::zip(word.iter(), guess_word.iter()) {
-        let validation = match (char_word == char_guessed, word.contains(char_guessed)) {
-            (true, _) => Validation::Correct(*char_guessed),
-            (false, true) => {
-                let char_count = chars_not_found.entry(char_word).or_insert(0);
-                *char_count += 1;
-                Validation::Present(*char_guessed)
-            }
-            (false, false) => {
-                let char_count = chars_not_found.entry(char_word).or_insert(0);
-                *char_count += 1;
-                Validation::NotInWord(*char_guessed)
-            }
-        };
+/// Start a practice game, with a random playable word, apart from the daily puzzles and their statistics.
+#[get("/practice")]
+pub async fn practice(
+    req: HttpRequest,
+    dictionaries: Data<Dictionaries>,
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    query: Query<PracticeQuery>,
+) -> Result<HttpResponse> {
+    check_length(&daily_words, query.length)?;
+    let app_state = dictionaries.get();
+    let words: Vec<&String> = app_state
+        .playable_word_list
+        .iter()
+        .filter(|w| query.length.is_none() || Some(w.chars().count()) == query.length)
+        .collect();
+    let word = match words.choose(&mut rand::thread_rng()) {
+        Some(word) => word.to_string(),
+        None => {
+            return Err(api_error(ApiError::Internal(
+                "No playable word".to_string(),
+            )))
+        }
+    };
 
-        validation_list.push(validation);
+    let (player_id, player_cookie) = get_or_create_player_id(&req);
+    let game_id = sessions.start_practice(&player_id, calendar.today().day, query.hard, &word)?;
+    debug!("Word of practice game {game_id} is : {word:?}");
+    let (session, day, word) = get_game(
+        &sessions,
+        &daily_words,
+        &calendar,
+        &game_id,
+        GameKind::Practice,
+    )?;
+
+    let mut response = HttpResponse::Ok();
+    if let Some(cookie) = player_cookie {
+        response.cookie(cookie);
     }
+    Ok(response.json(ResponseOrError::Response(get_hints(
+        &word, &session, &day, &sessions,
+    ))))
+}
 
-    // Second pass will correctly set Present validations based on the correct and present hits set on the previous loop
-    for validation in validation_list.iter_mut() {
-        if let Validation::Present(char_guessed) = *validation {
-            if chars_not_found.get(&char_guessed).unwrap_or(&0) > &0 {
-                let char_count = chars_not_found.get_mut(&char_guessed).expect("No char");
-                *char_count -= 1;
-            } else {
-                *validation = Validation::NotInWord(char_guessed);
-            }
-        }
+/// Create a challenge with a word chosen by the player, for their friends to find.
+#[post("/challenge")]
+pub async fn create_challenge(
+    req: HttpRequest,
+    dictionaries: Data<Dictionaries>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    challenge_body: Json<ChallengeBody>,
+) -> Result<Json<ResponseOrError<ChallengeResponse>>> {
+    let word = normalize(&challenge_body.word).map_err(|invalid| app_error(invalid.into()))?;
+    if !dictionaries.get().all_word_list.contains(&word) {
+        return Err(app_error(AppError::WordNotInDictionary(word)));
     }
 
-    validation_list
+    // Behind a proxy, the address of the client is the one it forwards
+    let client = req
+        .connection_info()
+        .realip_remote_addr()
+        .unwrap_or_default()
+        .to_string();
+    let challenge_id = sessions
+        .create_challenge(&word, calendar.today().day, &client)?
+        .ok_or_else(|| app_error(AppError::TooManyChallenges))?;
+    // The word must stay possible to find, even once removed from the word lists
+    dictionaries.keep([word]);
+    Ok(Json(ResponseOrError::Response(ChallengeResponse {
+        challenge_id,
+    })))
 }
 
-#[cfg(test)]
-mod tests {
-    use rand::prelude::*;
+#[get("/stats")]
+pub async fn stats(
+    req: HttpRequest,
+    storage: Data<dyn Storage>,
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    query: Query<ModeQuery>,
+) -> Result<Json<ResponseOrError<StatsResponse>>> {
+    check_length(&daily_words, query.length)?;
+    let results = match get_player_id(&req) {
+        Some(player_id) => storage.get_player_results(&player_id, query.length, false)?,
+        None => vec![],
+    };
 
-    use super::*;
+    Ok(Json(ResponseOrError::Response(compute_stats(
+        &results,
+        calendar.today().day,
+        sessions.max_attempts,
+    ))))
+}
 
-    #[test]
-    fn test_get_validation_list_empty() {
-        let result = get_validation_list(vec![], vec![]);
-        assert_eq!(result, []);
-    }
+#[get("/archive")]
+pub async fn archive(
+    req: HttpRequest,
+    storage: Data<dyn Storage>,
+    daily_words: Data<DailyWords>,
+    calendar: Data<GameCalendar>,
+    query: Query<ModeQuery>,
+) -> Result<Json<ResponseOrError<ArchiveResponse>>> {
+    check_length(&daily_words, query.length)?;
+    let results = match get_player_id(&req) {
+        Some(player_id) => storage.get_player_results(&player_id, query.length, true)?,
+        None => vec![],
+    };
+
+    Ok(Json(ResponseOrError::Response(list_archive(
+        &results,
+        &calendar,
+        &calendar.today(),
+    ))))
+}
+
+/// OpenAPI document of the API.
+#[get("/openapi.json")]
+pub async fn openapi_document() -> Json<serde_json::Value> {
+    Json(openapi::document())
+}
 
-    #[test]
-    fn test_get_validation_list_all_correct() {
-        let result = get_validation_list(vec!['v', 'd', 'l', 'a', '!'], vec!['v', 'd', 'l', 'a']);
-        assert_eq!(
-            result,
-            [
-                Validation::Correct('v'),
-                Validation::Correct('d'),
-                Validation::Correct('l'),
-                Validation::Correct('a'),
-            ]
-        );
+/// Reload the word lists from their files, after they have been edited.
+#[post("/admin/reload")]
+pub async fn reload(
+    req: HttpRequest,
+    admin_token: Data<AdminToken>,
+    dictionaries: Data<Dictionaries>,
+) -> Result<Json<ResponseOrError<ReloadResponse>>> {
+    if !admin_token.is_authorized(&req) {
+        warn!("Unauthorized reload of the dictionaries");
+        return Err(api_error(ApiError::Unauthorized));
     }
 
-    #[test]
-    fn test_get_validation_list_random() {
-        let mut rng = thread_rng();
+    match dictionaries.reload() {
+        Ok(app_state) => Ok(Json(ResponseOrError::Response(ReloadResponse {
+            all_words: app_state.all_word_list.len(),
+            playable_words: app_state.playable_word_list.len(),
+        }))),
+        // The files are read on the server, so an unreadable or invalid list is a failure of the server
+        Err(e) => {
+            error!("{e}");
+            Err(api_error(ApiError::Internal(e.to_string())))
+        }
+    }
+}
 
-        let word_len: usize = rng.gen_range(1..100);
+#[get("/game")]
+pub async fn game(
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    query: Query<GameQuery>,
+) -> Result<Json<ResponseOrError<GameResponse>>> {
+    let (session, day, word) = get_game(
+        &sessions,
+        &daily_words,
+        &calendar,
+        &query.game_id,
+        GameKind::new(query.puzzle, query.length, query.practice, &query.challenge),
+    )?;
+
+    let response = GameResponse {
+        hints: get_hints(&word, &session, &day, &sessions),
+        past_guesses: sessions.board(&session, &word),
+    };
+    Ok(Json(ResponseOrError::Response(response)))
+}
 
-        let mut word = vec!['0'; word_len];
-        let mut guess_word = vec!['0'; word_len];
+/// Reveal the word to find of a game, once it is finished or its day is over.
+#[get("/answer")]
+pub async fn answer(
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    definitions: Data<Definitions>,
+    query: Query<GameQuery>,
+) -> Result<Json<ResponseOrError<AnswerResponse>>> {
+    let (session, day, word) = get_game(
+        &sessions,
+        &daily_words,
+        &calendar,
+        &query.game_id,
+        GameKind::new(query.puzzle, query.length, query.practice, &query.challenge),
+    )?;
+
+    check_game_over(&session, &day, &calendar)?;
+
+    let response = AnswerResponse {
+        definition: definitions.get(&word).map(str::to_string),
+        word,
+    };
+    Ok(Json(ResponseOrError::Response(response)))
+}
 
-        rng.fill(&mut word[..]);
-        rng.fill(&mut guess_word[..]);
+/// Give the definition of the word to find or of a guess of a game, once it is over like for the answer.
+#[get("/definition/{word}")]
+pub async fn definition(
+    daily_words: Data<DailyWords>,
+    sessions: Data<Sessions>,
+    calendar: Data<GameCalendar>,
+    definitions: Data<Definitions>,
+    path: Path<String>,
+    query: Query<GameQuery>,
+) -> Result<Json<ResponseOrError<DefinitionResponse>>> {
+    let (session, day, word) = get_game(
+        &sessions,
+        &daily_words,
+        &calendar,
+        &query.game_id,
+        GameKind::new(query.puzzle, query.length, query.practice, &query.challenge),
+    )?;
+    check_game_over(&session, &day, &calendar)?;
+
+    let asked = path.into_inner();
+    let asked = match normalize(&asked) {
+        Ok(normalized) if normalized == word || session.guesses.contains(&normalized) => normalized,
+        Ok(normalized) => return Err(app_error(AppError::WordNotInGame(normalized))),
+        Err(_) => return Err(app_error(AppError::WordNotInGame(asked))),
+    };
 
-        let result = get_validation_list(word, guess_word.clone());
+    let response = DefinitionResponse {
+        definition: definitions.get(&asked).map(str::to_string),
+        word: asked,
+    };
+    Ok(Json(ResponseOrError::Response(response)))
+}
 
-        assert!(
-            Iterator::zip(result.iter(), guess_word.iter()).all(|(v, c)| {
-                match v {
-                    Validation::Correct(cv) => cv == c,
-                    Validation::NotInWord(cv) => cv == c,
-                    Validation::Present(cv) => cv == c,
-                }
-            })
-        );
+/// Check that a game is over: finished, or from a past day.
+fn check_game_over(session: &Session, day: &GameDay, calendar: &GameCalendar) -> Result<()> {
+    // Practice games and challenges have no day, they must be finished
+    let day_over = session.word.is_none() && day.day < calendar.today().day;
+    if !session.is_finished() && !day_over {
+        return Err(app_error(AppError::GameNotFinished));
     }
+    Ok(())
+}
+
+fn get_hints(word: &str, session: &Session, day: &GameDay, sessions: &Sessions) -> HintsResponse {
+    let word: Vec<char> = word.chars().collect();
 
-    #[test]
-    fn test_get_validation_list_mixed_validation() {
-        let result =
-            get_validation_list(vec!['a', 'b', 'c', 'd', 'e'], vec!['f', 'a', 'b', 'd', 'g']);
-        assert_eq!(
-            result,
-            [
-                Validation::NotInWord('f'),
-                Validation::Present('a'),
-                Validation::Present('b'),
-                Validation::Correct('d'),
-                Validation::NotInWord('g')
-            ]
-        );
-
-        let result = get_validation_list(vec!['a', 'b', 'b', 'a'], vec!['a', 'a', 'b', 'a']);
-        assert_eq!(
-            result,
-            [
-                Validation::Correct('a'),
-                Validation::NotInWord('a'),
-                Validation::Correct('b'),
-                Validation::Correct('a')
-            ]
-        );
-
-        let result = get_validation_list(vec!['a', 'b', 'b', 'a'], vec!['b', 'b', 'a', 'b']);
-        assert_eq!(
-            result,
-            [
-                Validation::Present('b'),
-                Validation::Correct('b'),
-                Validation::Present('a'),
-                Validation::NotInWord('b')
-            ]
-        );
+    HintsResponse {
+        game_id: session.id.clone(),
+        puzzle_number: day.puzzle_number,
+        next_rollover: day.next_rollover.timestamp(),
+        first_letter: word[0],
+        number_of_letters: word.len(),
+        first_letter_rule: sessions.rules.first_letter,
+        hard: session.hard,
+        length: session.length,
+        practice: session.word.is_some() && session.challenge.is_none(),
+        challenge: session.challenge.clone(),
+        max_attempts: sessions.max_attempts,
     }
 }
@@ -1,49 +1,93 @@
-use std::fmt::{Debug, Display};
+use std::fmt::Display;
 
-use actix_web::{error, http::StatusCode, HttpResponse, HttpResponseBuilder};
-use mdla_lib::model::AppError;
-use serde::Serialize;
+use actix_web::{error, http::StatusCode, HttpRequest, HttpResponse, HttpResponseBuilder};
+use log::warn;
+use mdla_lib::{
+    error::ApiError,
+    model::{AppError, ResponseOrError},
+};
 
-#[derive(Debug, Serialize)]
-pub enum ResponseOrError<A> {
-    Response(A),
-    Error(AppError),
-}
+use crate::storage::StorageError;
+
+/// An error answered by the API, as the `{"status": "error", "data": …}` of a [`ResponseOrError`].
+#[derive(Debug)]
+pub struct ErrorResponse(pub ApiError);
 
-impl<A: Debug> Display for ResponseOrError<A> {
+impl Display for ErrorResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &*self {
-            ResponseOrError::Error(AppError::BadWordLength {
-                size_expected,
-                size_received,
-                word_sent,
-            }) => {
-                write!(f, "Word should be {size_expected} letters but received {size_received} : {word_sent}")
-            }
-            ResponseOrError::Error(AppError::WordNotInDictionary(w)) => {
-                write!(f, "Word {w} is not in our dictionary")
-            }
-            ResponseOrError::Response(guess_response) => {
-                write!(f, "Guess response:  {guess_response:?}")
-            }
-        }
+        write!(f, "{}", self.0)
     }
 }
 
-impl<A: Debug + Serialize> error::ResponseError for ResponseOrError<A> {
+impl error::ResponseError for ErrorResponse {
     fn error_response(&self) -> HttpResponse {
-        HttpResponseBuilder::new(self.status_code()).json(self)
+        HttpResponseBuilder::new(self.status_code())
+            .json(ResponseOrError::<()>::Error(self.0.clone()))
     }
 
     fn status_code(&self) -> StatusCode {
-        match self {
-            ResponseOrError::Error(AppError::BadWordLength {
+        match &self.0 {
+            ApiError::App(AppError::BadWordLength {
                 size_expected: _,
                 size_received: _,
                 word_sent: _,
             }) => StatusCode::BAD_REQUEST,
-            ResponseOrError::Error(AppError::WordNotInDictionary(_)) => StatusCode::BAD_REQUEST,
-            ResponseOrError::Response(_) => StatusCode::OK,
+            ApiError::App(AppError::WordNotInDictionary(_)) => StatusCode::BAD_REQUEST,
+            ApiError::App(AppError::InvalidCharacters {
+                positions: _,
+                chars: _,
+            }) => StatusCode::BAD_REQUEST,
+            ApiError::App(AppError::WrongFirstLetter {
+                expected: _,
+                received: _,
+            }) => StatusCode::BAD_REQUEST,
+            ApiError::App(AppError::HardModeViolation {
+                letter: _,
+                position: _,
+            }) => StatusCode::BAD_REQUEST,
+            ApiError::App(AppError::GameNotFound(_)) => StatusCode::NOT_FOUND,
+            ApiError::App(AppError::GameFinished) => StatusCode::BAD_REQUEST,
+            ApiError::App(AppError::PuzzleNotAvailable(_)) => StatusCode::NOT_FOUND,
+            ApiError::App(AppError::LengthNotAvailable(_)) => StatusCode::NOT_FOUND,
+            ApiError::App(AppError::ChallengeNotFound(_)) => StatusCode::NOT_FOUND,
+            ApiError::App(AppError::GameNotFinished) => StatusCode::FORBIDDEN,
+            ApiError::App(AppError::WordNotInGame(_)) => StatusCode::NOT_FOUND,
+            ApiError::App(AppError::TooManyChallenges) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Internal(_) | ApiError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 }
+
+impl error::ResponseError for StorageError {
+    fn error_response(&self) -> HttpResponse {
+        log::error!("{self}");
+        let error = ApiError::Internal("The games can't be saved or loaded".to_string());
+        HttpResponseBuilder::new(self.status_code()).json(ResponseOrError::<()>::Error(error))
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+/// Turn any failure into an error of the API, answered with its envelope.
+pub fn api_error(error: ApiError) -> error::Error {
+    ErrorResponse(error).into()
+}
+
+/// Answer the requests the extractors can't read: malformed JSON, wrong content type, bad query or path parameters.
+pub fn bad_request<E: Display>(error: E, req: &HttpRequest) -> error::Error {
+    warn!("Bad request to {}: {error}", req.path());
+    api_error(ApiError::BadRequest(error.to_string()))
+}
+
+/// Answer the requests to a path that does not exist.
+pub async fn not_found(req: HttpRequest) -> error::Result<HttpResponse> {
+    Err(api_error(ApiError::NotFound(format!(
+        "Nothing at {}",
+        req.path()
+    ))))
+}
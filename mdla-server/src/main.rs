@@ -1,42 +1,72 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::{collections::HashMap, error::Error, fs, sync::Arc};
 
 use actix_web::{
     middleware::Logger,
-    web::{self, scope, Data},
-    App, HttpRequest, HttpServer,
+    web::{self, scope, Data, JsonConfig, PathConfig, QueryConfig},
+    App, HttpRequest, HttpServer, Scope,
 };
 
 use actix_files::{Files, NamedFile};
 
-use endpoints::{guess, hints};
+use admin::AdminToken;
+use calendar::GameCalendar;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use daily_word::{DailyWords, ScheduleExtension};
+use definitions::Definitions;
+use dictionary::Dictionaries;
+use endpoints::{
+    answer, archive, create_challenge, definition, game, guess, hints, openapi_document, practice,
+    reload, stats,
+};
 use env_logger::Env;
-use log::info;
-use mdla_lib::model::AppState;
+use errors::{api_error, bad_request, not_found};
+use log::{info, warn};
+use mdla_lib::{error::ApiError, model::WORD_LENGTHS, validation::Rules};
+use schedule::{extend_schedule, Schedule, DAYS_AHEAD, DAYS_AHEAD_MIN};
+use session::Sessions;
+use storage::{sqlite::SqliteStorage, Storage};
 use structopt::StructOpt;
 
+mod admin;
+mod calendar;
+mod daily_word;
+mod definitions;
+mod dictionary;
 mod endpoints;
 mod errors;
+mod openapi;
+mod player;
+mod schedule;
+mod session;
+mod statistics;
+mod storage;
+#[cfg(test)]
+mod test_files;
 
-fn get_words(file: &str) -> Vec<String> {
-    let file_words = File::open(file).expect("Open words file...");
-
-    let words: Vec<String> = BufReader::new(file_words)
-        .lines()
-        .enumerate()
-        .map(|(i, line)| line.unwrap_or_else(|e| panic!("Read line {i}: {e}")))
-        .collect();
-
-    let nb_words = words.len();
-    info!("{nb_words} words loaded from file {file}");
-
-    words
+async fn index(_req: HttpRequest) -> actix_web::Result<NamedFile> {
+    NamedFile::open("./resources/web-app/index.html")
+        .map_err(|e| api_error(ApiError::Internal(format!("Can't open the web app: {e}"))))
 }
 
-async fn index(_req: HttpRequest) -> actix_web::Result<NamedFile> {
-    Ok(NamedFile::open("./resources/web-app/index.html")?)
+/// Endpoints of the API under the given path, with the admin ones if there is a token to call them.
+fn api(path: &str, admin_token: &Option<Data<AdminToken>>) -> Scope {
+    let mut api = scope(path)
+        .service(guess)
+        .service(hints)
+        .service(practice)
+        .service(create_challenge)
+        .service(game)
+        .service(stats)
+        .service(archive)
+        .service(answer)
+        .service(definition)
+        .service(openapi_document)
+        .default_service(web::route().to(not_found));
+    if let Some(admin_token) = admin_token {
+        api = api.app_data(admin_token.clone()).service(reload);
+    }
+    api
 }
 
 #[derive(StructOpt, Debug)]
@@ -44,27 +74,225 @@ async fn index(_req: HttpRequest) -> actix_web::Result<NamedFile> {
 pub struct Cli {
     #[structopt(short = "p", long = "port", default_value = "8000")]
     port: String,
+
+    /// Number of guesses a player can make before losing the game
+    #[structopt(short = "a", long = "max-attempts", default_value = "6")]
+    max_attempts: usize,
+
+    /// Only accept guesses starting with the first letter of the word, as in Motus
+    #[structopt(long = "first-letter-rule")]
+    first_letter_rule: bool,
+
+    /// SQLite database file where games are saved, created if it does not exist
+    #[structopt(short = "d", long = "database", default_value = "./mdla.sqlite")]
+    database: String,
+
+    /// Timezone in which the daily word rolls over at midnight
+    #[structopt(short = "t", long = "timezone", default_value = "Europe/Paris")]
+    timezone: Tz,
+
+    /// Date of the first puzzle, numbered 1
+    #[structopt(short = "l", long = "launch-date", default_value = "2022-03-01")]
+    launch_date: NaiveDate,
+
+    /// File of the words of the daily puzzles, one per line starting with the puzzle number 1.
+    /// The puzzles of each length of words have their own file next to it, `word_schedule_7.db` for 7 letters
+    #[structopt(short = "s", long = "schedule", default_value = "./word_schedule.db")]
+    schedule: String,
+
+    /// File of the definitions of the words, shown with the answers: tab separated, or JSON if it ends with `.json`.
+    /// Optional
+    #[structopt(long = "definitions", default_value = "./definitions.tsv")]
+    definitions: String,
+
+    /// Secret mixed into the random picks of the daily words, so they can't be predicted.
+    /// Keep it private, and keep the same one as long as the server runs
+    #[structopt(long = "secret", env = "MDLA_SECRET", hide_env_values = true)]
+    secret: Option<String>,
+
+    /// File holding the secret, read when no secret is given, like a secret mounted in a container
+    #[structopt(long = "secret-file", env = "MDLA_SECRET_FILE")]
+    secret_file: Option<String>,
+
+    /// Run without a secret, for development only: the next daily words can then be predicted
+    #[structopt(long = "no-secret")]
+    no_secret: bool,
+
+    /// Token allowing to call the admin endpoints, which are disabled without it
+    #[structopt(long = "admin-token", env = "MDLA_ADMIN_TOKEN", hide_env_values = true)]
+    admin_token: Option<String>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Schedule the words of the next days, without changing the days already scheduled
+    ExtendSchedule {
+        /// Number of days after today to schedule
+        #[structopt(long = "days", default_value = "365")]
+        days: i64,
+    },
+    /// List the playable words without a definition, failing if there are any
+    CheckDefinitions,
+}
+
+/// Report the playable words without a definition.
+fn check_definitions(
+    definitions: &Definitions,
+    playable_words: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let missing = definitions.missing(playable_words);
+    for word in &missing {
+        println!("{word}");
+    }
+
+    if !missing.is_empty() {
+        return Err(format!(
+            "{} of the {} playable words have no definition",
+            missing.len(),
+            playable_words.len()
+        )
+        .into());
+    }
+    info!(
+        "All the {} playable words have a definition",
+        playable_words.len()
+    );
+    Ok(())
 }
 
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
+async fn main() -> Result<(), Box<dyn Error>> {
     env_logger::init_from_env(Env::default().default_filter_or("info"));
 
     let opt = Cli::from_args();
     let port = opt.port;
 
-    HttpServer::new(|| {
+    let calendar = GameCalendar::new(opt.timezone, opt.launch_date);
+    let today = calendar.today();
+
+    let dictionaries = Dictionaries::load("./word_list_all.db", "./word_list_playable.db")?;
+    let definitions = Definitions::load(&opt.definitions)?;
+    if let Some(Command::CheckDefinitions) = opt.command {
+        return check_definitions(&definitions, &dictionaries.get().playable_word_list);
+    }
+
+    let secret = match (opt.secret, &opt.secret_file) {
+        (None, Some(file)) if !opt.no_secret => Some(
+            fs::read_to_string(file)
+                .map_err(|e| format!("Can't read the secret file {file}: {e}"))?
+                .trim()
+                .to_string(),
+        ),
+        (secret, _) => secret,
+    };
+    let secret = match secret.filter(|secret| !secret.is_empty()) {
+        Some(secret) => secret,
+        None if opt.no_secret => {
+            warn!("Running without a secret, the next daily words can be predicted");
+            String::new()
+        }
+        None => {
+            return Err(
+                "No secret given with --secret, MDLA_SECRET or a secret file, the next daily words could be \
+                predicted: give one, or use --no-secret in development"
+                    .into(),
+            )
+        }
+    };
+    let mut schedules = HashMap::new();
+    for length in std::iter::once(None).chain(WORD_LENGTHS.map(Some)) {
+        let file = Schedule::file_for_length(&opt.schedule, length);
+        let mut schedule = Schedule::load(&file, &secret)?;
+        match opt.command {
+            Some(Command::ExtendSchedule { days }) => {
+                extend_schedule(
+                    &mut schedule,
+                    &file,
+                    &dictionaries.get().playable_word_list,
+                    length,
+                    today.puzzle_number + days,
+                    &secret,
+                )?;
+            }
+            _ if schedule.len() < today.puzzle_number + DAYS_AHEAD_MIN => {
+                warn!("Less than {DAYS_AHEAD_MIN} puzzles left in {file}, extending the schedule");
+                extend_schedule(
+                    &mut schedule,
+                    &file,
+                    &dictionaries.get().playable_word_list,
+                    length,
+                    today.puzzle_number + DAYS_AHEAD,
+                    &secret,
+                )?;
+            }
+            _ => {}
+        }
+        schedules.insert(length, schedule);
+    }
+    if opt.command.is_some() {
+        return Ok(());
+    }
+
+    let dictionaries = Data::new(dictionaries);
+    let definitions = Data::new(definitions);
+    let admin_token = opt.admin_token.map(|token| Data::new(AdminToken(token)));
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::open(&opt.database)?);
+    // The words of the puzzles must stay possible to find, even once removed from the word lists
+    dictionaries.keep(
+        schedules
+            .values()
+            .flat_map(|schedule| schedule.words().iter().cloned())
+            .chain(storage.get_puzzle_words()?),
+    );
+
+    // Dictionaries, storage and sessions must be shared between all the workers, so they are created outside of the factory
+    let rules = Rules {
+        first_letter: opt.first_letter_rule,
+        // Each player chooses it when starting a game
+        hard: false,
+    };
+    let sessions = Data::new(Sessions::new(opt.max_attempts, rules, storage.clone()));
+    // The schedules are extended while the server runs too, so that they never run out
+    let extension = ScheduleExtension {
+        file: opt.schedule.clone(),
+        secret,
+        dictionaries: dictionaries.clone().into_inner(),
+    };
+    let daily_words = Data::new(DailyWords::new(storage.clone(), schedules, Some(extension)));
+    let storage = Data::from(storage);
+    let calendar = Data::new(calendar);
+
+    HttpServer::new(move || {
         App::new()
-            .app_data(Data::new(AppState {
-                all_word_list: get_words("./word_list_all.db"),
-                playable_word_list: get_words("./word_list_playable.db"),
-            }))
+            .app_data(dictionaries.clone())
+            .app_data(definitions.clone())
+            .app_data(storage.clone())
+            .app_data(sessions.clone())
+            .app_data(calendar.clone())
+            .app_data(daily_words.clone())
+            .app_data(JsonConfig::default().error_handler(bad_request))
+            .app_data(QueryConfig::default().error_handler(bad_request))
+            .app_data(PathConfig::default().error_handler(bad_request))
             .wrap(Logger::default())
-            .service(scope("/api").service(guess).service(hints))
+            .service(api(openapi::API_PATH, &admin_token))
+            // The first unversioned paths of the API, kept for the clients using them
+            .service(api("/api", &admin_token))
             .route("/", web::get().to(index))
-            .service(Files::new("/", "./resources/web-app/"))
+            .route("/archive", web::get().to(index))
+            .route("/archive/{puzzle}", web::get().to(index))
+            .route("/lettres/{length}", web::get().to(index))
+            .route("/entrainement", web::get().to(index))
+            .route("/c/{id}", web::get().to(index))
+            .service(
+                Files::new("/", "./resources/web-app/").default_handler(web::route().to(not_found)),
+            )
     })
     .bind(format!("0.0.0.0:{port}"))?
     .run()
-    .await
+    .await?;
+
+    Ok(())
 }
@@ -0,0 +1,245 @@
+use mdla_lib::model::{
+    AnswerResponseOrError, AppError, ArchiveResponseOrError, ChallengeBody,
+    ChallengeResponseOrError, DefinitionResponseOrError, GameQuery, GameResponseOrError, GuessBody,
+    GuessResponseOrError, HintsQuery, HintsResponseOrError, ModeQuery, PracticeQuery,
+    StatsResponseOrError,
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    JsonSchema,
+};
+use serde_json::{json, Value};
+
+/// Path of the current version of the API.
+pub const API_PATH: &str = "/api/v1";
+
+/// An endpoint of the API, described from the types of mdla-lib it reads and answers.
+struct Operation {
+    method: &'static str,
+    path: &'static str,
+    summary: &'static str,
+    parameters: Vec<Value>,
+    body: Option<Value>,
+    response: Value,
+    /// Whether the endpoint can answer an error of the player, with a 4xx status
+    player_errors: bool,
+}
+
+impl Operation {
+    /// A GET endpoint, with the fields of `Q` as query parameters.
+    fn get<Q: JsonSchema, R: JsonSchema>(
+        gen: &mut SchemaGenerator,
+        path: &'static str,
+        summary: &'static str,
+    ) -> Self {
+        let query = Q::json_schema(gen).into_object();
+        let object = query.object.unwrap_or_default();
+        let parameters = object
+            .properties
+            .iter()
+            .map(|(name, schema)| {
+                json!({
+                    "name": name,
+                    "in": "query",
+                    "required": object.required.contains(name),
+                    "schema": schema,
+                })
+            })
+            .collect();
+
+        Self {
+            method: "get",
+            path,
+            summary,
+            parameters,
+            body: None,
+            response: json!(gen.subschema_for::<R>()),
+            player_errors: false,
+        }
+    }
+
+    /// A POST endpoint, reading a JSON body `B`.
+    fn post<B: JsonSchema, R: JsonSchema>(
+        gen: &mut SchemaGenerator,
+        path: &'static str,
+        summary: &'static str,
+    ) -> Self {
+        Self {
+            method: "post",
+            path,
+            summary,
+            parameters: vec![],
+            body: Some(json!(gen.subschema_for::<B>())),
+            response: json!(gen.subschema_for::<R>()),
+            player_errors: false,
+        }
+    }
+
+    fn path_parameter(mut self, name: &str) -> Self {
+        self.parameters.push(json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "schema": {"type": "string"},
+        }));
+        self
+    }
+
+    fn player_errors(mut self) -> Self {
+        self.player_errors = true;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let content = json!({"application/json": {"schema": self.response}});
+        let mut responses = json!({"200": {"description": "Success", "content": content}});
+        if self.player_errors {
+            responses["4XX"] = json!({"description": "Error of the player", "content": content});
+        }
+        // Any endpoint can fail on the server, with an `internal_error` envelope
+        responses["5XX"] = json!({"description": "Error of the server", "content": content});
+
+        let mut operation = json!({
+            "summary": self.summary,
+            "parameters": self.parameters,
+            "responses": responses,
+        });
+        if let Some(body) = &self.body {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": {"application/json": {"schema": body}},
+            });
+        }
+        operation
+    }
+}
+
+/// OpenAPI document of the API, with the schemas of the model of mdla-lib.
+///
+/// The admin endpoints are left out.
+pub fn document() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let operations = [
+        Operation::get::<HintsQuery, HintsResponseOrError>(
+            &mut gen,
+            "/hints",
+            "Start or resume a game of a puzzle, or of a challenge",
+        )
+        .player_errors(),
+        Operation::get::<PracticeQuery, HintsResponseOrError>(
+            &mut gen,
+            "/practice",
+            "Start a practice game, with a random word",
+        )
+        .player_errors(),
+        Operation::post::<GuessBody, GuessResponseOrError>(&mut gen, "/guess", "Guess a word")
+            .player_errors(),
+        Operation::post::<ChallengeBody, ChallengeResponseOrError>(
+            &mut gen,
+            "/challenge",
+            "Create a challenge with a word chosen by the player",
+        )
+        .player_errors(),
+        Operation::get::<GameQuery, GameResponseOrError>(
+            &mut gen,
+            "/game",
+            "Get a game, with its past guesses",
+        )
+        .player_errors(),
+        Operation::get::<ModeQuery, StatsResponseOrError>(
+            &mut gen,
+            "/stats",
+            "Statistics of the player",
+        )
+        .player_errors(),
+        Operation::get::<ModeQuery, ArchiveResponseOrError>(
+            &mut gen,
+            "/archive",
+            "Past puzzles, with the results of the player",
+        )
+        .player_errors(),
+        Operation::get::<GameQuery, AnswerResponseOrError>(
+            &mut gen,
+            "/answer",
+            "Word to find of a game, once it is over",
+        )
+        .player_errors(),
+        Operation::get::<GameQuery, DefinitionResponseOrError>(
+            &mut gen,
+            "/definition/{word}",
+            "Definition of the word to find or of a guess of a game, once it is over",
+        )
+        .path_parameter("word")
+        .player_errors(),
+    ];
+
+    // Codes and details of the errors of the player, answered in the envelope of the errors
+    gen.subschema_for::<AppError>();
+
+    let mut paths = json!({});
+    for operation in &operations {
+        paths[operation.path][operation.method] = operation.to_json();
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "MdlA",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "API of MdlA. The player is identified by a cookie, set when starting a game.",
+        },
+        "servers": [{"url": API_PATH}],
+        "paths": paths,
+        "components": {"schemas": gen.definitions()},
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn references(value: &Value, found: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(reference)) = map.get("$ref") {
+                    found.push(reference.clone());
+                }
+                map.values().for_each(|v| references(v, found));
+            }
+            Value::Array(values) => values.iter().for_each(|v| references(v, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_document() {
+        let document = document();
+
+        assert_eq!(
+            document["paths"]["/guess"]["post"]["requestBody"]["content"]["application/json"]
+                ["schema"]["$ref"],
+            "#/components/schemas/GuessBody"
+        );
+        let parameters = document["paths"]["/hints"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        assert!(parameters
+            .iter()
+            .any(|p| p["name"] == "hard" && p["required"] == false));
+        assert!(document["components"]["schemas"]["AppError"].is_object());
+        assert!(document["components"]["schemas"]["ErrorEnvelope"].is_object());
+        assert!(document["paths"]["/stats"]["get"]["responses"]["4XX"].is_object());
+        assert!(document["paths"]["/archive"]["get"]["responses"]["5XX"].is_object());
+
+        let mut found = vec![];
+        references(&document, &mut found);
+        assert!(!found.is_empty());
+        for reference in found {
+            let name = reference.trim_start_matches("#/components/schemas/");
+            assert!(
+                document["components"]["schemas"][name].is_object(),
+                "{reference} is not defined"
+            );
+        }
+    }
+}
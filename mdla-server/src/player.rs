@@ -0,0 +1,27 @@
+use actix_web::{
+    cookie::{time::Duration, Cookie},
+    HttpRequest,
+};
+
+const PLAYER_COOKIE: &str = "mdla_player";
+
+/// Id of the player making the request, if they already got one.
+pub fn get_player_id(req: &HttpRequest) -> Option<String> {
+    req.cookie(PLAYER_COOKIE).map(|c| c.value().to_string())
+}
+
+/// Id of the player making the request, and the cookie to set if it's a new player.
+pub fn get_or_create_player_id(req: &HttpRequest) -> (String, Option<Cookie<'static>>) {
+    match get_player_id(req) {
+        Some(id) => (id, None),
+        None => {
+            let id = format!("{:032x}", rand::random::<u128>());
+            let cookie = Cookie::build(PLAYER_COOKIE, id.clone())
+                .path("/")
+                .http_only(true)
+                .max_age(Duration::days(10 * 365))
+                .finish();
+            (id, Some(cookie))
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, ErrorKind, Write},
+};
+
+use log::{info, warn};
+use rand::{prelude::StdRng, Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+/// The schedules are extended once fewer puzzles than this are left after today.
+pub const DAYS_AHEAD_MIN: i64 = 30;
+/// Number of puzzles scheduled after today when a schedule is extended.
+pub const DAYS_AHEAD: i64 = 365;
+/// First line of a schedule file, followed by the fingerprint of the secret its words were picked with.
+const FINGERPRINT_HEADER: &str = "# secret ";
+
+/// Words of the daily puzzles, the first one being the word of the puzzle number 1.
+///
+/// The schedule is only ever extended by appending words at its end, so the words of the days
+/// already published never change, even if the playable words are edited afterwards.
+/// A word is not picked again until all the playable words have been picked.
+///
+/// The words are picked at random with a seed mixing a secret of the server with the puzzle number,
+/// so they can't be predicted from the playable words and this algorithm.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Schedule {
+    words: Vec<String>,
+}
+
+impl Schedule {
+    /// Load the schedule from a file, with one word per line after the fingerprint of the secret.
+    /// A missing file is an empty schedule.
+    ///
+    /// A file written with another secret is refused, as its next words would be picked with both secrets.
+    /// A file written before the fingerprints is given the one of the current secret.
+    pub fn load(file: &str, secret: &str) -> io::Result<Self> {
+        let lines = match File::open(file) {
+            Ok(f) => BufReader::new(f)
+                .lines()
+                .collect::<io::Result<Vec<String>>>()?,
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e),
+        };
+        let fingerprint = lines
+            .first()
+            .and_then(|line| line.strip_prefix(FINGERPRINT_HEADER));
+        let words: Vec<String> = lines
+            .iter()
+            .filter(|w| !w.is_empty() && !w.starts_with('#'))
+            .cloned()
+            .collect();
+
+        match fingerprint {
+            Some(fingerprint) if fingerprint != secret_fingerprint(secret) => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("The schedule {file} was written with another secret"),
+                ));
+            }
+            None if !words.is_empty() => {
+                warn!(
+                    "No fingerprint of the secret in {file}, adding the one of the current secret"
+                );
+                let mut content = format!("{FINGERPRINT_HEADER}{}\n", secret_fingerprint(secret));
+                for word in &words {
+                    content.push_str(word);
+                    content.push('\n');
+                }
+                fs::write(file, content)?;
+            }
+            _ => {}
+        }
+
+        info!("{} puzzles scheduled in file {file}", words.len());
+        Ok(Self { words })
+    }
+
+    pub fn len(&self) -> i64 {
+        self.words.len() as i64
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn word(&self, puzzle_number: i64) -> Option<&str> {
+        let index = usize::try_from(puzzle_number - 1).ok()?;
+        self.words.get(index).map(String::as_str)
+    }
+
+    /// Schedule words up to the given puzzle number and return the words added.
+    pub fn extend(&mut self, playable_words: &[String], until: i64, secret: &str) -> Vec<String> {
+        let playable: HashSet<&str> = playable_words.iter().map(String::as_str).collect();
+
+        // Replay the schedule to know which words have already been picked since the last time all of them were
+        let mut used: HashSet<String> = HashSet::new();
+        let mut used_playable = 0;
+        for word in self.words.iter() {
+            if used_playable == playable.len() {
+                used.clear();
+                used_playable = 0;
+            }
+            if used.insert(word.clone()) && playable.contains(word.as_str()) {
+                used_playable += 1;
+            }
+        }
+
+        let first_new = self.words.len();
+        let mut remaining: Vec<&String> = if used_playable == playable.len() {
+            vec![]
+        } else {
+            playable_words
+                .iter()
+                .filter(|w| !used.contains(*w))
+                .collect()
+        };
+        for puzzle_number in self.len() + 1..=until {
+            if remaining.is_empty() {
+                remaining = playable_words.iter().collect();
+            }
+            if remaining.is_empty() {
+                break;
+            }
+
+            let mut rng = puzzle_rng(secret, puzzle_number);
+            let index = rng.gen_range(0..remaining.len());
+            self.words.push(remaining.remove(index).clone());
+        }
+
+        self.words[first_new..].to_vec()
+    }
+
+    /// File of the schedule of the puzzles with words of the given length, next to the classic one:
+    /// `./word_schedule_7.db` for `./word_schedule.db`.
+    pub fn file_for_length(file: &str, length: Option<usize>) -> String {
+        let length = match length {
+            Some(length) => length,
+            None => return file.to_string(),
+        };
+        match file.rsplit_once('.') {
+            Some((stem, extension)) if !extension.contains('/') => {
+                format!("{stem}_{length}.{extension}")
+            }
+            _ => format!("{file}_{length}"),
+        }
+    }
+
+    /// Append words to the schedule file, creating it with the fingerprint of the secret if needed.
+    pub fn append_to(file: &str, words: &[String], secret: &str) -> io::Result<()> {
+        let mut f = OpenOptions::new().create(true).append(true).open(file)?;
+        if f.metadata()?.len() == 0 {
+            writeln!(f, "{FINGERPRINT_HEADER}{}", secret_fingerprint(secret))?;
+        }
+        for word in words {
+            writeln!(f, "{word}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Make sure the schedule has a word for every puzzle until the given one, appending new words to its file if needed.
+///
+/// The words are picked among the playable words of the given length, or all of them for the classic puzzles.
+pub fn extend_schedule(
+    schedule: &mut Schedule,
+    file: &str,
+    playable_words: &[String],
+    length: Option<usize>,
+    until: i64,
+    secret: &str,
+) -> Result<(), Box<dyn Error>> {
+    let playable_words: Vec<String> = playable_words
+        .iter()
+        .filter(|w| length.is_none() || Some(w.chars().count()) == length)
+        .cloned()
+        .collect();
+    let added = schedule.extend(&playable_words, until, secret);
+    Schedule::append_to(file, &added, secret)?;
+    info!("{} puzzles added to the schedule {file}", added.len());
+
+    if schedule.len() < until {
+        return Err(format!("Not enough playable words to schedule until puzzle {until}").into());
+    }
+    Ok(())
+}
+
+/// Fingerprint of the secret written in the schedule files, which does not give the secret away.
+fn secret_fingerprint(secret: &str) -> String {
+    let digest = Sha256::new()
+        .chain_update(b"mdla-schedule")
+        .chain_update(secret.as_bytes())
+        .finalize();
+    format!("{digest:x}")[..16].to_string()
+}
+
+/// Random generator of a puzzle, seeded with a hash of the secret and the puzzle number.
+fn puzzle_rng(secret: &str, puzzle_number: i64) -> StdRng {
+    let seed = Sha256::new()
+        .chain_update(secret.as_bytes())
+        .chain_update(puzzle_number.to_le_bytes())
+        .finalize();
+    StdRng::from_seed(seed.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_files::TestFile;
+
+    const SECRET: &str = "secret";
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_extend_no_repeat_until_exhausted() {
+        let playable = words(&["ABAISSE", "ABATTRE", "ABBAYE", "ABEILLE"]);
+        let mut schedule = Schedule::default();
+
+        let added = schedule.extend(&playable, 10, SECRET);
+        assert_eq!(added.len(), 10);
+        assert_eq!(schedule.len(), 10);
+
+        let mut first_cycle = added[0..4].to_vec();
+        first_cycle.sort();
+        assert_eq!(first_cycle, playable);
+        let mut second_cycle = added[4..8].to_vec();
+        second_cycle.sort();
+        assert_eq!(second_cycle, playable);
+    }
+
+    #[test]
+    fn test_extend_is_append_only_and_deterministic() {
+        let playable = words(&["ABAISSE", "ABATTRE", "ABBAYE", "ABEILLE", "ABOLIR"]);
+
+        let mut schedule = Schedule::default();
+        schedule.extend(&playable, 3, SECRET);
+        let published = schedule.clone();
+
+        // Extending in one or many steps gives the same schedule
+        schedule.extend(&playable, 8, SECRET);
+        let mut other = Schedule::default();
+        other.extend(&playable, 8, SECRET);
+        assert_eq!(schedule, other);
+
+        // Editing the words list does not change the days already scheduled
+        let edited = words(&["ABAISSE", "ABBAYE", "ABEILLE", "ABOLIR", "ABONDANT"]);
+        let mut edited_schedule = published.clone();
+        edited_schedule.extend(&edited, 8, SECRET);
+        for n in 1..=3 {
+            assert_eq!(edited_schedule.word(n), published.word(n));
+        }
+        assert!(edited_schedule.words[3..]
+            .iter()
+            .all(|w| edited.contains(w)));
+    }
+
+    #[test]
+    fn test_extend_depends_on_secret() {
+        let playable: Vec<String> = (0..100).map(|i| format!("MOT{i}")).collect();
+
+        let mut schedule = Schedule::default();
+        schedule.extend(&playable, 20, SECRET);
+        let mut same_secret = Schedule::default();
+        same_secret.extend(&playable, 20, SECRET);
+        assert_eq!(schedule, same_secret);
+
+        // Without the secret, the words can't be predicted by running the algorithm
+        for guessed_secret in ["", "other", "secret "] {
+            let mut guessed = Schedule::default();
+            guessed.extend(&playable, 20, guessed_secret);
+            let same_words = (1..=20)
+                .filter(|n| guessed.word(*n) == schedule.word(*n))
+                .count();
+            assert!(same_words < 5, "{same_words} words guessed");
+        }
+    }
+
+    #[test]
+    fn test_file_for_length() {
+        assert_eq!(
+            Schedule::file_for_length("./word_schedule.db", None),
+            "./word_schedule.db"
+        );
+        assert_eq!(
+            Schedule::file_for_length("./word_schedule.db", Some(7)),
+            "./word_schedule_7.db"
+        );
+        assert_eq!(
+            Schedule::file_for_length("../data/schedule", Some(9)),
+            "../data/schedule_9"
+        );
+    }
+
+    #[test]
+    fn test_load_refuses_other_secret() {
+        let test_file = TestFile::new("secret.db");
+        let file = test_file.path();
+        Schedule::append_to(file, &words(&["ABAISSE", "ABBAYE"]), SECRET).unwrap();
+        Schedule::append_to(file, &words(&["ABEILLE"]), SECRET).unwrap();
+
+        assert_eq!(Schedule::load(file, SECRET).unwrap().len(), 3);
+        assert_eq!(
+            Schedule::load(file, "").unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        // A file written before the fingerprints is given the one of the current secret
+        test_file.write("ABAISSE\nABBAYE\n");
+        assert_eq!(Schedule::load(file, SECRET).unwrap().len(), 2);
+        assert!(Schedule::load(file, "").is_err());
+    }
+
+    #[test]
+    fn test_word() {
+        let mut schedule = Schedule::default();
+        schedule.extend(&words(&["ABAISSE"]), 1, SECRET);
+
+        assert_eq!(schedule.word(0), None);
+        assert_eq!(schedule.word(1), Some("ABAISSE"));
+        assert_eq!(schedule.word(2), None);
+    }
+}
@@ -0,0 +1,574 @@
+use std::sync::Arc;
+
+use log::info;
+use mdla_lib::{
+    model::{GameOutcome, GuessResponse, Validation},
+    scoring::score,
+    validation::Rules,
+};
+
+use crate::storage::{Storage, StorageError};
+
+/// Challenges a client can create each day.
+pub const MAX_CHALLENGES_PER_CLIENT: usize = 20;
+/// Challenges all the clients can create each day, which bounds the storage whatever address they claim.
+pub const MAX_CHALLENGES_PER_DAY: usize = 1000;
+/// Days a challenge can be played, from the day it is created.
+pub const CHALLENGE_RETENTION_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id: String,
+    pub player_id: Option<String>,
+    pub day: i64,
+    /// Whether the game is in hard mode, chosen when it starts
+    pub hard: bool,
+    /// Length of the words of the puzzle, none for the classic puzzle
+    pub length: Option<usize>,
+    /// Word to find of a practice game, picked at random when it starts.
+    /// None for the games of the puzzles, whose word is the one of their day.
+    pub word: Option<String>,
+    /// Id of the challenge of the game, whose word is then the one of the challenge
+    pub challenge: Option<String>,
+    /// Whether the puzzle is played from the archive, after its day: such games are left out of the
+    /// statistics
+    pub archive: bool,
+    pub guesses: Vec<String>,
+    pub result: Option<GameResult>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub won: bool,
+    pub attempts: usize,
+}
+
+impl Session {
+    pub fn is_finished(&self) -> bool {
+        self.result.is_some()
+    }
+}
+
+/// Games of the players, indexed by the game id given to them with the hints.
+///
+/// A game is only valid for the day it was started on: once the daily word rolls over,
+/// the game can't be played nor restored anymore.
+#[derive(Debug)]
+pub struct Sessions {
+    pub max_attempts: usize,
+    pub rules: Rules,
+    storage: Arc<dyn Storage>,
+}
+
+impl Sessions {
+    pub fn new(max_attempts: usize, rules: Rules, storage: Arc<dyn Storage>) -> Self {
+        Self {
+            max_attempts,
+            rules,
+            storage,
+        }
+    }
+
+    /// Start a game of a player for the puzzle of the given day and length, and return its id.
+    /// A player only gets one game per puzzle: if they already started it, that game is resumed,
+    /// with its guesses. Its mode is switched to the one asked for only until the first guess.
+    ///
+    /// A game of the archive, played after the day of its puzzle, is not part of the statistics.
+    pub fn start(
+        &self,
+        player_id: &str,
+        day: i64,
+        length: Option<usize>,
+        hard: bool,
+        archive: bool,
+    ) -> Result<String, StorageError> {
+        self.storage.find_or_create_session(&Session {
+            id: new_id(),
+            player_id: Some(player_id.to_string()),
+            day,
+            hard,
+            length,
+            word: None,
+            challenge: None,
+            archive,
+            guesses: vec![],
+            result: None,
+        })
+    }
+
+    /// Start a new practice game of a player, to find the given word, and return its id.
+    /// Practice games never expire and are not part of the statistics.
+    pub fn start_practice(
+        &self,
+        player_id: &str,
+        day: i64,
+        hard: bool,
+        word: &str,
+    ) -> Result<String, StorageError> {
+        let length = Some(word.chars().count());
+        self.create(player_id, day, length, hard, Some(word.to_string()), None)
+    }
+
+    /// Save a word chosen by a player for their friends to find, and return the id of the challenge.
+    /// The id is random, so it does not reveal the word.
+    ///
+    /// None if too many challenges were created today by the client, or by all of them since the address
+    /// of a client can be forged. The challenges created [`CHALLENGE_RETENTION_DAYS`] days ago are deleted.
+    pub fn create_challenge(
+        &self,
+        word: &str,
+        day: i64,
+        client: &str,
+    ) -> Result<Option<String>, StorageError> {
+        if self.storage.count_challenges(day, Some(client))? >= MAX_CHALLENGES_PER_CLIENT
+            || self.storage.count_challenges(day, None)? >= MAX_CHALLENGES_PER_DAY
+        {
+            return Ok(None);
+        }
+        let deleted = self
+            .storage
+            .delete_challenges_before(day + 1 - CHALLENGE_RETENTION_DAYS)?;
+        if deleted > 0 {
+            info!("{deleted} challenges older than {CHALLENGE_RETENTION_DAYS} days deleted");
+        }
+
+        let id = new_id();
+        self.storage.create_challenge(&id, word, day, client)?;
+        Ok(Some(id))
+    }
+
+    /// Start a new game of a player for a challenge and return its id, none if the challenge does not exist.
+    /// Like practice games, challenges never expire and are not part of the statistics.
+    pub fn start_challenge(
+        &self,
+        player_id: &str,
+        day: i64,
+        hard: bool,
+        challenge: &str,
+    ) -> Result<Option<String>, StorageError> {
+        let word = match self.storage.get_challenge_word(challenge)? {
+            Some(word) => word,
+            None => return Ok(None),
+        };
+        let length = Some(word.chars().count());
+        self.create(
+            player_id,
+            day,
+            length,
+            hard,
+            Some(word),
+            Some(challenge.to_string()),
+        )
+        .map(Some)
+    }
+
+    fn create(
+        &self,
+        player_id: &str,
+        day: i64,
+        length: Option<usize>,
+        hard: bool,
+        word: Option<String>,
+        challenge: Option<String>,
+    ) -> Result<String, StorageError> {
+        let id = new_id();
+
+        self.storage.create_session(&Session {
+            id: id.clone(),
+            player_id: Some(player_id.to_string()),
+            day,
+            hard,
+            length,
+            word,
+            challenge,
+            archive: false,
+            guesses: vec![],
+            result: None,
+        })?;
+        Ok(id)
+    }
+
+    /// Rules of a game: the ones of the server, and the mode chosen by the player.
+    pub fn rules(&self, session: &Session) -> Rules {
+        Rules {
+            hard: session.hard,
+            ..self.rules
+        }
+    }
+
+    /// Get a game of the puzzle of the given day and length, if it exists and has not expired.
+    pub fn get(
+        &self,
+        id: &str,
+        day: i64,
+        length: Option<usize>,
+    ) -> Result<Option<Session>, StorageError> {
+        Ok(self.storage.get_session(id)?.filter(|session| {
+            session.word.is_none() && session.day == day && session.length == length
+        }))
+    }
+
+    /// Get a practice game, if it exists.
+    pub fn get_practice(&self, id: &str) -> Result<Option<Session>, StorageError> {
+        Ok(self
+            .storage
+            .get_session(id)?
+            .filter(|session| session.word.is_some() && session.challenge.is_none()))
+    }
+
+    /// Get a game of a challenge, if it exists.
+    pub fn get_challenge(
+        &self,
+        id: &str,
+        challenge: &str,
+    ) -> Result<Option<Session>, StorageError> {
+        Ok(self
+            .storage
+            .get_session(id)?
+            .filter(|session| session.challenge.as_deref() == Some(challenge)))
+    }
+
+    /// Record a new guess for the game and return the outcome of the game after it, or none if the
+    /// game was finished in the meantime, by another guess sent at the same time.
+    pub fn record_guess(
+        &self,
+        session: &Session,
+        word: &str,
+        guess: &str,
+        found: bool,
+    ) -> Result<Option<GameOutcome>, StorageError> {
+        let result = |attempts| match self.outcome(attempts, word, found) {
+            GameOutcome::Ongoing { attempts_left: _ } => None,
+            _ => Some(GameResult {
+                won: found,
+                attempts,
+            }),
+        };
+        let attempts = self.storage.add_guess(&session.id, guess, &result)?;
+        Ok(attempts.map(|attempts| self.outcome(attempts, word, found)))
+    }
+
+    /// Replay all the guesses of a game, as they were answered to the player.
+    pub fn board(&self, session: &Session, word: &str) -> Vec<GuessResponse> {
+        session
+            .guesses
+            .iter()
+            .enumerate()
+            .map(|(i, guess)| {
+                let validation_list = score(word, guess);
+                let found = validation_list
+                    .iter()
+                    .all(|v| matches!(v, Validation::Correct(_)));
+                GuessResponse {
+                    outcome: self.outcome(i + 1, word, found),
+                    validation_list,
+                }
+            })
+            .collect()
+    }
+
+    fn outcome(&self, attempts: usize, word: &str, found: bool) -> GameOutcome {
+        match (found, attempts >= self.max_attempts) {
+            (true, _) => GameOutcome::Won,
+            (false, true) => GameOutcome::GameOver {
+                word: word.to_string(),
+            },
+            (false, false) => GameOutcome::Ongoing {
+                attempts_left: self.max_attempts - attempts,
+            },
+        }
+    }
+}
+
+/// A new random id, for a game or a challenge.
+fn new_id() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storage::memory::MemoryStorage;
+
+    use super::*;
+
+    fn sessions(max_attempts: usize) -> Sessions {
+        Sessions::new(
+            max_attempts,
+            Rules::default(),
+            Arc::new(MemoryStorage::default()),
+        )
+    }
+
+    #[test]
+    fn test_record_guess_until_game_over() {
+        let sessions = sessions(2);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert_eq!(
+            sessions
+                .record_guess(&session, "MDLA", "ABCD", false)
+                .unwrap(),
+            Some(GameOutcome::Ongoing { attempts_left: 1 })
+        );
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert!(!session.is_finished());
+        assert_eq!(
+            sessions
+                .record_guess(&session, "MDLA", "ABCE", false)
+                .unwrap(),
+            Some(GameOutcome::GameOver {
+                word: "MDLA".to_string()
+            })
+        );
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert_eq!(
+            session.result,
+            Some(GameResult {
+                won: false,
+                attempts: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_record_guess_won() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert_eq!(
+            sessions
+                .record_guess(&session, "MDLA", "MDLA", true)
+                .unwrap(),
+            Some(GameOutcome::Won)
+        );
+        assert!(sessions.get(&id, 1, None).unwrap().unwrap().is_finished());
+    }
+
+    #[test]
+    fn test_record_guess_of_finished_game() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+
+        // Two guesses sent at the same time, both read the game before the other one is recorded
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert_eq!(
+            sessions
+                .record_guess(&session, "MDLA", "MDLA", true)
+                .unwrap(),
+            Some(GameOutcome::Won)
+        );
+        assert_eq!(
+            sessions
+                .record_guess(&session, "MDLA", "MALD", false)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            sessions.get(&id, 1, None).unwrap().unwrap().guesses,
+            ["MDLA"]
+        );
+    }
+
+    #[test]
+    fn test_get_unknown_or_expired_game() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+
+        assert_eq!(sessions.get("nope", 1, None).unwrap(), None);
+        assert_eq!(sessions.get(&id, 2, None).unwrap(), None);
+        // A game is only valid for the length of words it was started with
+        assert_eq!(sessions.get(&id, 1, Some(7)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_board_replays_guesses() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        sessions
+            .record_guess(&session, "MDLA", "MALD", false)
+            .unwrap();
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        sessions
+            .record_guess(&session, "MDLA", "MDLA", true)
+            .unwrap();
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        let board = sessions.board(&session, "MDLA");
+
+        assert_eq!(
+            board,
+            [
+                GuessResponse {
+                    validation_list: score("MDLA", "MALD"),
+                    outcome: GameOutcome::Ongoing { attempts_left: 5 },
+                },
+                GuessResponse {
+                    validation_list: score("MDLA", "MDLA"),
+                    outcome: GameOutcome::Won,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_start_resumes_game_of_puzzle() {
+        let sessions = sessions(2);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        sessions
+            .record_guess(&session, "MDLA", "ABCD", false)
+            .unwrap();
+
+        // Starting the puzzle again gives back the same game, with its guesses
+        assert_eq!(sessions.start("player", 1, None, false, false).unwrap(), id);
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert_eq!(session.guesses, ["ABCD"]);
+
+        assert_ne!(sessions.start("other", 1, None, false, false).unwrap(), id);
+        assert_ne!(sessions.start("player", 2, None, false, false).unwrap(), id);
+        assert_ne!(
+            sessions.start("player", 1, Some(7), false, false).unwrap(),
+            id
+        );
+    }
+
+    #[test]
+    fn test_start_switches_mode_until_first_guess() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+
+        assert_eq!(sessions.start("player", 1, None, true, false).unwrap(), id);
+        assert!(sessions.get(&id, 1, None).unwrap().unwrap().hard);
+        assert_eq!(sessions.start("player", 1, None, false, false).unwrap(), id);
+        assert!(!sessions.get(&id, 1, None).unwrap().unwrap().hard);
+
+        // Once a guess is made, the mode can't change anymore
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        sessions
+            .record_guess(&session, "MDLA", "ABCD", false)
+            .unwrap();
+        assert_eq!(sessions.start("player", 1, None, true, false).unwrap(), id);
+        assert!(!sessions.get(&id, 1, None).unwrap().unwrap().hard);
+    }
+
+    #[test]
+    fn test_resumed_from_archive() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, false, false).unwrap();
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        sessions
+            .record_guess(&session, "MDLA", "ABCD", false)
+            .unwrap();
+        assert!(!session.archive);
+
+        // A game left unfinished on its day and resumed later is played from the archive
+        assert_eq!(sessions.start("player", 1, None, false, true).unwrap(), id);
+        assert!(sessions.get(&id, 1, None).unwrap().unwrap().archive);
+    }
+
+    #[test]
+    fn test_practice_game() {
+        let sessions = sessions(6);
+        let id = sessions
+            .start_practice("player", 1, false, "ABAISSE")
+            .unwrap();
+
+        let session = sessions.get_practice(&id).unwrap().unwrap();
+        assert_eq!(session.word.as_deref(), Some("ABAISSE"));
+        assert_eq!(session.length, Some(7));
+        // A practice game is not the game of a puzzle, and the other way round
+        assert_eq!(sessions.get(&id, 1, Some(7)).unwrap(), None);
+        let daily_id = sessions.start("player", 1, None, false, false).unwrap();
+        assert_eq!(sessions.get_practice(&daily_id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_challenge_game() {
+        let sessions = sessions(6);
+        let challenge = sessions
+            .create_challenge("ABAISSE", 1, "127.0.0.1")
+            .unwrap()
+            .unwrap();
+        assert!(!challenge.contains("ABAISSE"));
+        assert_eq!(
+            sessions
+                .start_challenge("player", 1, false, "nope")
+                .unwrap(),
+            None
+        );
+
+        let id = sessions
+            .start_challenge("player", 1, false, &challenge)
+            .unwrap()
+            .unwrap();
+        let session = sessions.get_challenge(&id, &challenge).unwrap().unwrap();
+        assert_eq!(session.word.as_deref(), Some("ABAISSE"));
+        assert_eq!(sessions.get_challenge(&id, "other").unwrap(), None);
+        assert_eq!(sessions.get_practice(&id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_challenges_are_limited_and_expire() {
+        let sessions = sessions(6);
+        let first = sessions
+            .create_challenge("ABAISSE", 1, "127.0.0.1")
+            .unwrap()
+            .unwrap();
+        for _ in 1..MAX_CHALLENGES_PER_CLIENT {
+            assert!(sessions
+                .create_challenge("ABAISSE", 1, "127.0.0.1")
+                .unwrap()
+                .is_some());
+        }
+        assert_eq!(
+            sessions
+                .create_challenge("ABAISSE", 1, "127.0.0.1")
+                .unwrap(),
+            None
+        );
+        // Other clients, and the same one the next day, can still create challenges
+        assert!(sessions
+            .create_challenge("ABAISSE", 1, "127.0.0.2")
+            .unwrap()
+            .is_some());
+        assert!(sessions
+            .create_challenge("ABAISSE", 2, "127.0.0.1")
+            .unwrap()
+            .is_some());
+
+        assert!(sessions
+            .start_challenge("player", 1, false, &first)
+            .unwrap()
+            .is_some());
+        sessions
+            .create_challenge("ABBAYE", 1 + CHALLENGE_RETENTION_DAYS, "127.0.0.1")
+            .unwrap();
+        assert_eq!(
+            sessions
+                .start_challenge("player", 1, false, &first)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rules_of_hard_game() {
+        let sessions = sessions(6);
+        let id = sessions.start("player", 1, None, true, false).unwrap();
+
+        let session = sessions.get(&id, 1, None).unwrap().unwrap();
+        assert!(session.hard);
+        assert_eq!(
+            sessions.rules(&session),
+            Rules {
+                first_letter: false,
+                hard: true
+            }
+        );
+    }
+}
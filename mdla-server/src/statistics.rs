@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use mdla_lib::model::{ArchivePuzzle, ArchiveResponse, StatsResponse};
+
+use crate::{
+    calendar::{GameCalendar, GameDay},
+    session::GameResult,
+};
+
+/// Compute the statistics of a player from the results of their games, sorted by day.
+///
+/// Only the first game finished each day is taken into account.
+pub fn compute_stats(
+    results: &[(i64, GameResult)],
+    today: i64,
+    max_attempts: usize,
+) -> StatsResponse {
+    let mut results = results.to_vec();
+    results.dedup_by_key(|(day, _)| *day);
+
+    let games_played = results.len();
+    let games_won = results.iter().filter(|(_, r)| r.won).count();
+
+    let mut guess_distribution = vec![0; max_attempts];
+    for (_, result) in results.iter().filter(|(_, r)| r.won) {
+        if let Some(count) = guess_distribution.get_mut(result.attempts.saturating_sub(1)) {
+            *count += 1;
+        }
+    }
+
+    let mut max_streak = 0;
+    let mut streak = 0;
+    let mut last_won_day = None;
+    for (day, result) in results.iter() {
+        streak = match (result.won, last_won_day) {
+            (false, _) => 0,
+            (true, Some(last)) if last + 1 == *day => streak + 1,
+            (true, _) => 1,
+        };
+        last_won_day = if result.won { Some(*day) } else { None };
+        max_streak = max_streak.max(streak);
+    }
+    // The streak is still going if the player won today's game, or yesterday's one and has not played today yet
+    let current_streak = match last_won_day {
+        Some(day) if day >= today - 1 => streak,
+        _ => 0,
+    };
+
+    StatsResponse {
+        games_played,
+        win_percentage: (games_won * 100)
+            .checked_div(games_played)
+            .unwrap_or_default() as u8,
+        current_streak,
+        max_streak,
+        guess_distribution,
+    }
+}
+
+/// List the puzzles before today, with the result of the first game the player finished for each of them.
+pub fn list_archive(
+    results: &[(i64, GameResult)],
+    calendar: &GameCalendar,
+    today: &GameDay,
+) -> ArchiveResponse {
+    let mut won_by_puzzle = HashMap::new();
+    for (day, result) in results {
+        won_by_puzzle
+            .entry(calendar.puzzle_number(*day))
+            .or_insert(result.won);
+    }
+
+    ArchiveResponse {
+        puzzles: (1..today.puzzle_number)
+            .rev()
+            .map(|puzzle_number| ArchivePuzzle {
+                puzzle_number,
+                won: won_by_puzzle.get(&puzzle_number).copied(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{NaiveDate, TimeZone, Utc};
+    use mdla_lib::validation::Rules;
+
+    use crate::{
+        session::Sessions,
+        storage::{memory::MemoryStorage, Storage},
+    };
+
+    use super::*;
+
+    fn won(attempts: usize) -> GameResult {
+        GameResult {
+            won: true,
+            attempts,
+        }
+    }
+
+    fn lost(attempts: usize) -> GameResult {
+        GameResult {
+            won: false,
+            attempts,
+        }
+    }
+
+    #[test]
+    fn test_compute_stats_no_game() {
+        assert_eq!(
+            compute_stats(&[], 10, 6),
+            StatsResponse {
+                games_played: 0,
+                win_percentage: 0,
+                current_streak: 0,
+                max_streak: 0,
+                guess_distribution: vec![0; 6],
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let results = [
+            (1, won(3)),
+            (2, won(4)),
+            (3, won(3)),
+            (4, lost(6)),
+            (6, won(1)),
+            (7, won(2)),
+            (7, lost(6)),
+        ];
+        assert_eq!(
+            compute_stats(&results, 8, 6),
+            StatsResponse {
+                games_played: 6,
+                win_percentage: 83,
+                current_streak: 2,
+                max_streak: 3,
+                guess_distribution: vec![1, 1, 2, 1, 0, 0],
+            }
+        );
+    }
+
+    #[test]
+    fn test_compute_stats_broken_streak() {
+        let results = [(1, won(3)), (2, won(4))];
+        assert_eq!(compute_stats(&results, 2, 6).current_streak, 2);
+        assert_eq!(compute_stats(&results, 3, 6).current_streak, 2);
+        assert_eq!(compute_stats(&results, 4, 6).current_streak, 0);
+
+        let results = [(1, won(3)), (2, lost(6))];
+        assert_eq!(compute_stats(&results, 2, 6).current_streak, 0);
+        assert_eq!(compute_stats(&results, 2, 6).max_streak, 1);
+    }
+
+    #[test]
+    fn test_list_archive() {
+        let calendar =
+            GameCalendar::new(chrono_tz::UTC, NaiveDate::from_ymd_opt(2022, 3, 1).unwrap());
+        let now = Utc.with_ymd_and_hms(2022, 3, 5, 12, 0, 0).unwrap();
+        let day = |n| calendar.puzzle_day_at(n, now).unwrap().day;
+
+        let results = [
+            (day(1), won(3)),
+            (day(3), lost(6)),
+            (day(3), won(2)),
+            (day(5), won(1)),
+        ];
+        let archive = list_archive(&results, &calendar, &calendar.day_at(now));
+
+        assert_eq!(
+            archive.puzzles,
+            [
+                ArchivePuzzle {
+                    puzzle_number: 4,
+                    won: None
+                },
+                ArchivePuzzle {
+                    puzzle_number: 3,
+                    won: Some(false)
+                },
+                ArchivePuzzle {
+                    puzzle_number: 2,
+                    won: None
+                },
+                ArchivePuzzle {
+                    puzzle_number: 1,
+                    won: Some(true)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_late_archive_win_does_not_change_stats() {
+        let storage = Arc::new(MemoryStorage::default());
+        let sessions = Sessions::new(6, Rules::default(), storage.clone());
+        let win = |day, archive| {
+            let id = sessions.start("player", day, None, false, archive).unwrap();
+            let session = sessions.get(&id, day, None).unwrap().unwrap();
+            sessions
+                .record_guess(&session, "MDLA", "MDLA", true)
+                .unwrap();
+        };
+        // Puzzle 2 is missed, then won from the archive once puzzle 3 is over
+        win(1, false);
+        win(3, false);
+        win(2, true);
+
+        let results = storage.get_player_results("player", None, false).unwrap();
+        let stats = compute_stats(&results, 3, 6);
+        assert_eq!(stats.games_played, 2);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.max_streak, 1);
+        // The archive still shows the puzzle as won
+        let results = storage.get_player_results("player", None, true).unwrap();
+        assert!(results.contains(&(2, won(1))));
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use crate::session::{GameResult, Session};
+
+use super::{Storage, StorageError};
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    sessions: HashMap<String, Session>,
+    // Ids of the finished sessions, in the order they were finished
+    results: Vec<String>,
+    daily_words: HashMap<(i64, Option<usize>), String>,
+    // Word, day and client of each challenge
+    challenges: HashMap<String, (String, i64, String)>,
+}
+
+/// Storage keeping everything in memory, lost when the server stops. Handy for tests.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    data: Mutex<MemoryData>,
+}
+
+impl Storage for MemoryStorage {
+    fn create_session(&self, session: &Session) -> Result<(), StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        data.sessions.insert(session.id.clone(), session.clone());
+        Ok(())
+    }
+
+    fn find_or_create_session(&self, session: &Session) -> Result<String, StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        let existing = data.sessions.values_mut().find(|existing| {
+            existing.player_id == session.player_id
+                && existing.day == session.day
+                && existing.length == session.length
+                && existing.word.is_none()
+        });
+        if let Some(existing) = existing {
+            if existing.guesses.is_empty() {
+                existing.hard = session.hard;
+            }
+            if session.archive && !existing.is_finished() {
+                existing.archive = true;
+            }
+            return Ok(existing.id.clone());
+        }
+        data.sessions.insert(session.id.clone(), session.clone());
+        Ok(session.id.clone())
+    }
+
+    fn get_session(&self, id: &str) -> Result<Option<Session>, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        Ok(data.sessions.get(id).cloned())
+    }
+
+    fn add_guess(
+        &self,
+        id: &str,
+        guess: &str,
+        result: &dyn Fn(usize) -> Option<GameResult>,
+    ) -> Result<Option<usize>, StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        let session = data
+            .sessions
+            .get_mut(id)
+            .ok_or_else(|| StorageError(format!("No session {id}")))?;
+        if session.is_finished() {
+            return Ok(None);
+        }
+        session.guesses.push(guess.to_string());
+        let attempts = session.guesses.len();
+        session.result = result(attempts);
+        if session.is_finished() {
+            data.results.push(id.to_string());
+        }
+        Ok(Some(attempts))
+    }
+
+    fn get_player_results(
+        &self,
+        player_id: &str,
+        length: Option<usize>,
+        with_archive: bool,
+    ) -> Result<Vec<(i64, GameResult)>, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        let mut results: Vec<(i64, GameResult)> = data
+            .results
+            .iter()
+            .filter_map(|id| data.sessions.get(id))
+            .filter(|session| session.player_id.as_deref() == Some(player_id))
+            .filter(|session| session.length == length && session.word.is_none())
+            .filter(|session| with_archive || !session.archive)
+            .filter_map(|session| Some((session.day, session.result.clone()?)))
+            .collect();
+        // Stable sort, so games of the same day stay in the order they were finished
+        results.sort_by_key(|(day, _)| *day);
+        Ok(results)
+    }
+
+    fn get_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+    ) -> Result<Option<String>, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        Ok(data.daily_words.get(&(day, length)).cloned())
+    }
+
+    fn save_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+        word: &str,
+    ) -> Result<(), StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        data.daily_words
+            .entry((day, length))
+            .or_insert_with(|| word.to_string());
+        Ok(())
+    }
+
+    fn create_challenge(
+        &self,
+        id: &str,
+        word: &str,
+        day: i64,
+        client: &str,
+    ) -> Result<(), StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        data.challenges
+            .insert(id.to_string(), (word.to_string(), day, client.to_string()));
+        Ok(())
+    }
+
+    fn count_challenges(&self, day: i64, client: Option<&str>) -> Result<usize, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        Ok(data
+            .challenges
+            .values()
+            .filter(|(_, d, c)| *d == day && (client.is_none() || client == Some(c)))
+            .count())
+    }
+
+    fn delete_challenges_before(&self, day: i64) -> Result<usize, StorageError> {
+        let mut data = self.data.lock().expect("Lock memory storage");
+        let before = data.challenges.len();
+        data.challenges.retain(|_, (_, d, _)| *d >= day);
+        let MemoryData {
+            sessions,
+            results,
+            challenges,
+            ..
+        } = &mut *data;
+        sessions.retain(|_, session| match &session.challenge {
+            Some(challenge) => challenges.contains_key(challenge),
+            None => true,
+        });
+        results.retain(|id| sessions.contains_key(id));
+        Ok(before - data.challenges.len())
+    }
+
+    fn get_challenge_word(&self, id: &str) -> Result<Option<String>, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        Ok(data.challenges.get(id).map(|(word, _, _)| word.clone()))
+    }
+
+    fn get_puzzle_words(&self) -> Result<Vec<String>, StorageError> {
+        let data = self.data.lock().expect("Lock memory storage");
+        let words: HashSet<&String> = data
+            .daily_words
+            .values()
+            .chain(data.challenges.values().map(|(word, _, _)| word))
+            .collect();
+        Ok(words.into_iter().cloned().collect())
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt::{Debug, Display};
+
+use crate::session::{GameResult, Session};
+
+#[cfg(test)]
+pub mod memory;
+pub mod sqlite;
+
+#[derive(Debug)]
+pub struct StorageError(pub String);
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Storage error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Everything the server needs to remember between two requests, or two restarts.
+pub trait Storage: Send + Sync + Debug {
+    fn create_session(&self, session: &Session) -> Result<(), StorageError>;
+
+    /// Create a game of a puzzle, unless its player already has one for the same day and length:
+    /// returns the id of the game to play, the existing one if any. An existing game without any
+    /// guess yet takes the mode of the new one, the mode of the others can't change anymore. An
+    /// existing game not finished yet is part of the archive from the time it is resumed from it.
+    fn find_or_create_session(&self, session: &Session) -> Result<String, StorageError>;
+
+    fn get_session(&self, id: &str) -> Result<Option<Session>, StorageError>;
+
+    /// Add a guess to a game that is not finished, and save its result if the guess ends it, all at
+    /// once. `result` gives the result of the game from its number of guesses, this one included, or
+    /// none if the game goes on. Returns that number of guesses, or none if the game was already
+    /// finished.
+    fn add_guess(
+        &self,
+        id: &str,
+        guess: &str,
+        result: &dyn Fn(usize) -> Option<GameResult>,
+    ) -> Result<Option<usize>, StorageError>;
+
+    /// Results of all the games of puzzles finished by a player with words of the given length
+    /// (`None` for the classic puzzles), sorted by day then by end of the game. Practice games are left out,
+    /// and so are the games of the archive unless `with_archive` is set.
+    fn get_player_results(
+        &self,
+        player_id: &str,
+        length: Option<usize>,
+        with_archive: bool,
+    ) -> Result<Vec<(i64, GameResult)>, StorageError>;
+
+    fn get_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+    ) -> Result<Option<String>, StorageError>;
+
+    fn save_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+        word: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Save a challenge created on the given day by a client, known by its address.
+    fn create_challenge(
+        &self,
+        id: &str,
+        word: &str,
+        day: i64,
+        client: &str,
+    ) -> Result<(), StorageError>;
+
+    /// Number of challenges created on the given day, by the given client or by all of them.
+    fn count_challenges(&self, day: i64, client: Option<&str>) -> Result<usize, StorageError>;
+
+    /// Delete the challenges created before the given day, with their games, and return how many were deleted.
+    fn delete_challenges_before(&self, day: i64) -> Result<usize, StorageError>;
+
+    fn get_challenge_word(&self, id: &str) -> Result<Option<String>, StorageError>;
+
+    /// Words of all the puzzles and challenges ever played, without duplicates.
+    fn get_puzzle_words(&self) -> Result<Vec<String>, StorageError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{memory::MemoryStorage, sqlite::SqliteStorage, *};
+
+    fn check_storage(storage: &dyn Storage) {
+        let session = Session {
+            id: "abc".to_string(),
+            player_id: Some("player".to_string()),
+            day: 42,
+            hard: true,
+            length: None,
+            word: None,
+            challenge: None,
+            archive: false,
+            guesses: vec![],
+            result: None,
+        };
+        storage.create_session(&session).unwrap();
+        assert_eq!(storage.get_session("abc").unwrap(), Some(session.clone()));
+        assert_eq!(storage.get_session("ghi").unwrap(), None);
+
+        let result = GameResult {
+            won: true,
+            attempts: 2,
+        };
+        let won_at_second = |attempts| (attempts == 2).then(|| result.clone());
+        assert_eq!(
+            storage.add_guess("abc", "ABAISSE", &won_at_second).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            storage.add_guess("abc", "ABATTRE", &won_at_second).unwrap(),
+            Some(2)
+        );
+        // The game is over, no more guesses
+        assert_eq!(
+            storage.add_guess("abc", "ABBAYES", &won_at_second).unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.get_session("abc").unwrap(),
+            Some(Session {
+                guesses: vec!["ABAISSE".to_string(), "ABATTRE".to_string()],
+                result: Some(result),
+                ..session.clone()
+            })
+        );
+
+        storage
+            .create_session(&Session {
+                id: "def".to_string(),
+                day: 41,
+                hard: false,
+                ..session.clone()
+            })
+            .unwrap();
+        storage
+            .add_guess("def", "ABAISSE", &|_| {
+                Some(GameResult {
+                    won: false,
+                    attempts: 6,
+                })
+            })
+            .unwrap();
+        storage
+            .create_session(&Session {
+                id: "ghi".to_string(),
+                length: Some(7),
+                ..session.clone()
+            })
+            .unwrap();
+        storage
+            .add_guess("ghi", "ABAISSE", &|_| {
+                Some(GameResult {
+                    won: true,
+                    attempts: 3,
+                })
+            })
+            .unwrap();
+        assert_eq!(storage.get_session("ghi").unwrap().unwrap().length, Some(7));
+        let practice = Session {
+            id: "jkl".to_string(),
+            length: Some(7),
+            word: Some("ABAISSE".to_string()),
+            ..session.clone()
+        };
+        storage.create_session(&practice).unwrap();
+        storage
+            .add_guess("jkl", "ABAISSE", &|_| {
+                Some(GameResult {
+                    won: true,
+                    attempts: 1,
+                })
+            })
+            .unwrap();
+        assert_eq!(
+            storage.get_session("jkl").unwrap().unwrap().word,
+            practice.word
+        );
+        // A player has only one game per puzzle, practice games and challenges aside
+        let again = Session {
+            id: "pqr".to_string(),
+            ..session.clone()
+        };
+        let easy = Session {
+            hard: false,
+            ..again.clone()
+        };
+        assert_eq!(storage.find_or_create_session(&easy).unwrap(), "abc");
+        assert_eq!(storage.get_session("pqr").unwrap(), None);
+        // The game has guesses, its mode stays the same
+        assert!(storage.get_session("abc").unwrap().unwrap().hard);
+        let next_day = Session { day: 43, ..again };
+        assert_eq!(storage.find_or_create_session(&next_day).unwrap(), "pqr");
+        assert_eq!(storage.get_session("pqr").unwrap(), Some(next_day.clone()));
+        // Without guesses, it takes the mode asked for
+        let next_day_easy = Session {
+            id: "stu".to_string(),
+            hard: false,
+            ..next_day
+        };
+        assert_eq!(
+            storage.find_or_create_session(&next_day_easy).unwrap(),
+            "pqr"
+        );
+        assert!(!storage.get_session("pqr").unwrap().unwrap().hard);
+
+        assert_eq!(storage.get_challenge_word("xyz").unwrap(), None);
+        storage
+            .create_challenge("xyz", "ABAISSE", 42, "127.0.0.1")
+            .unwrap();
+        assert_eq!(
+            storage.get_challenge_word("xyz").unwrap(),
+            Some("ABAISSE".to_string())
+        );
+        let challenge = Session {
+            id: "mno".to_string(),
+            challenge: Some("xyz".to_string()),
+            ..practice
+        };
+        storage.create_session(&challenge).unwrap();
+        assert_eq!(storage.get_session("mno").unwrap(), Some(challenge));
+        let archive = Session {
+            id: "vwx".to_string(),
+            day: 40,
+            archive: true,
+            ..session.clone()
+        };
+        storage.create_session(&archive).unwrap();
+        storage
+            .add_guess("vwx", "ABAISSE", &|_| {
+                Some(GameResult {
+                    won: true,
+                    attempts: 1,
+                })
+            })
+            .unwrap();
+        assert!(storage.get_session("vwx").unwrap().unwrap().archive);
+        assert_eq!(
+            storage.get_player_results("player", None, true).unwrap()[0],
+            (
+                40,
+                GameResult {
+                    won: true,
+                    attempts: 1
+                }
+            )
+        );
+        assert_eq!(
+            storage.get_player_results("player", None, false).unwrap(),
+            [
+                (
+                    41,
+                    GameResult {
+                        won: false,
+                        attempts: 6
+                    }
+                ),
+                (
+                    42,
+                    GameResult {
+                        won: true,
+                        attempts: 2
+                    }
+                )
+            ]
+        );
+        assert_eq!(
+            storage
+                .get_player_results("player", Some(7), false)
+                .unwrap(),
+            [(
+                42,
+                GameResult {
+                    won: true,
+                    attempts: 3
+                }
+            )]
+        );
+        assert_eq!(
+            storage
+                .get_player_results("player", Some(8), false)
+                .unwrap(),
+            []
+        );
+        assert_eq!(
+            storage.get_player_results("nobody", None, false).unwrap(),
+            []
+        );
+
+        assert_eq!(storage.get_daily_word(42, None).unwrap(), None);
+        storage.save_daily_word(42, None, "ABAISSE").unwrap();
+        storage.save_daily_word(42, Some(6), "ABBAYE").unwrap();
+        assert_eq!(
+            storage.get_daily_word(42, None).unwrap(),
+            Some("ABAISSE".to_string())
+        );
+        assert_eq!(
+            storage.get_daily_word(42, Some(6)).unwrap(),
+            Some("ABBAYE".to_string())
+        );
+        assert_eq!(storage.get_daily_word(42, Some(7)).unwrap(), None);
+
+        storage.save_daily_word(43, None, "ABATTRE").unwrap();
+        let mut words = storage.get_puzzle_words().unwrap();
+        words.sort();
+        assert_eq!(words, ["ABAISSE", "ABATTRE", "ABBAYE"]);
+
+        storage
+            .create_challenge("stu", "ABEILLE", 43, "127.0.0.2")
+            .unwrap();
+        assert_eq!(storage.count_challenges(42, None).unwrap(), 1);
+        assert_eq!(storage.count_challenges(43, Some("127.0.0.2")).unwrap(), 1);
+        assert_eq!(storage.count_challenges(43, Some("127.0.0.1")).unwrap(), 0);
+        // Old challenges are deleted with their games
+        assert_eq!(storage.delete_challenges_before(43).unwrap(), 1);
+        assert_eq!(storage.get_challenge_word("xyz").unwrap(), None);
+        assert_eq!(storage.get_session("mno").unwrap(), None);
+        assert_eq!(
+            storage.get_challenge_word("stu").unwrap(),
+            Some("ABEILLE".to_string())
+        );
+        assert_eq!(storage.count_challenges(42, None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_memory_storage() {
+        check_storage(&MemoryStorage::default());
+    }
+
+    #[test]
+    fn test_sqlite_storage() {
+        check_storage(&SqliteStorage::open(":memory:").unwrap());
+    }
+}
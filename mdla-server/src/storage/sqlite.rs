@@ -0,0 +1,444 @@
+use std::sync::Mutex;
+
+use log::info;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+use crate::session::{GameResult, Session};
+
+use super::{Storage, StorageError};
+
+/// Schema migrations, applied in order. The index of the last applied one is kept in the `user_version` of the database.
+///
+/// Never edit a migration already released: add a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: sessions, guesses, results and daily words
+    "CREATE TABLE sessions (
+        id TEXT PRIMARY KEY,
+        day INTEGER NOT NULL
+    );
+    CREATE TABLE guesses (
+        session_id TEXT NOT NULL REFERENCES sessions(id),
+        position INTEGER NOT NULL,
+        guess TEXT NOT NULL,
+        PRIMARY KEY (session_id, position)
+    );
+    CREATE TABLE results (
+        session_id TEXT PRIMARY KEY REFERENCES sessions(id),
+        won INTEGER NOT NULL,
+        attempts INTEGER NOT NULL
+    );
+    CREATE TABLE daily_words (
+        day INTEGER PRIMARY KEY,
+        word TEXT NOT NULL
+    );",
+    // 2: players, to compute their statistics
+    "ALTER TABLE sessions ADD COLUMN player_id TEXT;
+    CREATE INDEX sessions_player_id ON sessions(player_id);",
+    // 3: hard mode
+    "ALTER TABLE sessions ADD COLUMN hard INTEGER NOT NULL DEFAULT 0;",
+    // 4: puzzles of each length of words, 0 being the classic puzzle
+    "ALTER TABLE sessions ADD COLUMN length INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE daily_words_by_length (
+        day INTEGER NOT NULL,
+        length INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        PRIMARY KEY (day, length)
+    );
+    INSERT INTO daily_words_by_length (day, length, word) SELECT day, 0, word FROM daily_words;
+    DROP TABLE daily_words;
+    ALTER TABLE daily_words_by_length RENAME TO daily_words;",
+    // 5: practice games, with their own word
+    "ALTER TABLE sessions ADD COLUMN word TEXT;",
+    // 6: challenges, with a word chosen by a player
+    "CREATE TABLE challenges (
+        id TEXT PRIMARY KEY,
+        word TEXT NOT NULL
+    );
+    ALTER TABLE sessions ADD COLUMN challenge_id TEXT REFERENCES challenges(id);",
+    // 7: puzzles played from the archive, left out of the statistics
+    "ALTER TABLE sessions ADD COLUMN archive INTEGER NOT NULL DEFAULT 0;",
+    // 8: day and client of the challenges, to limit and expire them. The challenges already created are dated
+    // from the last day played, so they are kept as long as the new ones
+    "ALTER TABLE challenges ADD COLUMN day INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE challenges ADD COLUMN client TEXT NOT NULL DEFAULT '';
+    UPDATE challenges SET day = (SELECT COALESCE(MAX(day), 0) FROM sessions);
+    CREATE INDEX challenges_day ON challenges(day, client);",
+];
+
+/// Value of the `length` columns: the length of the words, or 0 for the classic puzzle.
+fn length_column(length: Option<usize>) -> usize {
+    length.unwrap_or(0)
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        StorageError(e.to_string())
+    }
+}
+
+/// Storage in an embedded SQLite database file.
+#[derive(Debug)]
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Open the database, creating it if needed, and bring its schema up to date.
+    pub fn open(path: &str) -> Result<Self, StorageError> {
+        let mut connection = Connection::open(path)?;
+        migrate(&mut connection)?;
+        info!("Database {path} ready");
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), StorageError> {
+    let version: usize =
+        connection.query_row("SELECT user_version FROM pragma_user_version", [], |row| {
+            row.get(0)
+        })?;
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        info!("Apply database migration {}", i + 1);
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", i + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+/// Insert a session with its guesses and result, as part of a transaction.
+fn insert_session(transaction: &Transaction, session: &Session) -> Result<(), StorageError> {
+    transaction.execute(
+        "INSERT INTO sessions (id, player_id, day, hard, length, word, challenge_id, archive)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            session.id,
+            session.player_id,
+            session.day,
+            session.hard,
+            length_column(session.length),
+            session.word,
+            session.challenge,
+            session.archive
+        ],
+    )?;
+    for (position, guess) in session.guesses.iter().enumerate() {
+        transaction.execute(
+            "INSERT INTO guesses (session_id, position, guess) VALUES (?1, ?2, ?3)",
+            params![session.id, position, guess],
+        )?;
+    }
+    if let Some(result) = &session.result {
+        transaction.execute(
+            "INSERT INTO results (session_id, won, attempts) VALUES (?1, ?2, ?3)",
+            params![session.id, result.won, result.attempts],
+        )?;
+    }
+    Ok(())
+}
+
+impl Storage for SqliteStorage {
+    fn create_session(&self, session: &Session) -> Result<(), StorageError> {
+        let mut connection = self.connection.lock().expect("Lock database");
+        let transaction = connection.transaction()?;
+        insert_session(&transaction, session)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn find_or_create_session(&self, session: &Session) -> Result<String, StorageError> {
+        let mut connection = self.connection.lock().expect("Lock database");
+        let transaction = connection.transaction()?;
+        let existing: Option<String> = transaction
+            .query_row(
+                "SELECT id FROM sessions
+                WHERE player_id = ?1 AND day = ?2 AND length = ?3
+                AND word IS NULL AND challenge_id IS NULL
+                ORDER BY rowid LIMIT 1",
+                params![
+                    session.player_id,
+                    session.day,
+                    length_column(session.length)
+                ],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match existing {
+            Some(id) => {
+                transaction.execute(
+                    "UPDATE sessions SET hard = ?2
+                    WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM guesses WHERE session_id = ?1)",
+                    params![id, session.hard],
+                )?;
+                transaction.execute(
+                    "UPDATE sessions SET archive = 1
+                    WHERE id = ?1 AND ?2 AND NOT EXISTS (SELECT 1 FROM results WHERE session_id = ?1)",
+                    params![id, session.archive],
+                )?;
+                transaction.commit()?;
+                return Ok(id);
+            }
+            None => insert_session(&transaction, session)?,
+        }
+        transaction.commit()?;
+        Ok(session.id.clone())
+    }
+
+    fn get_session(&self, id: &str) -> Result<Option<Session>, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+
+        let session = connection
+            .query_row(
+                "SELECT player_id, day, hard, length, word, challenge_id, archive FROM sessions WHERE id = ?1",
+                [id],
+                |row| {
+                    let length: usize = row.get(3)?;
+                    Ok(Session {
+                        id: id.to_string(),
+                        player_id: row.get(0)?,
+                        day: row.get(1)?,
+                        hard: row.get(2)?,
+                        length: if length == 0 { None } else { Some(length) },
+                        word: row.get(4)?,
+                        challenge: row.get(5)?,
+                        archive: row.get(6)?,
+                        guesses: vec![],
+                        result: None,
+                    })
+                },
+            )
+            .optional()?;
+        let session = match session {
+            Some(session) => session,
+            None => return Ok(None),
+        };
+
+        let guesses = connection
+            .prepare("SELECT guess FROM guesses WHERE session_id = ?1 ORDER BY position")?
+            .query_map([id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+
+        let result = connection
+            .query_row(
+                "SELECT won, attempts FROM results WHERE session_id = ?1",
+                [id],
+                |row| {
+                    Ok(GameResult {
+                        won: row.get(0)?,
+                        attempts: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(Some(Session {
+            guesses,
+            result,
+            ..session
+        }))
+    }
+
+    fn add_guess(
+        &self,
+        id: &str,
+        guess: &str,
+        result: &dyn Fn(usize) -> Option<GameResult>,
+    ) -> Result<Option<usize>, StorageError> {
+        let mut connection = self.connection.lock().expect("Lock database");
+        let transaction = connection.transaction()?;
+        let finished: bool = transaction.query_row(
+            "SELECT EXISTS (SELECT 1 FROM results WHERE session_id = ?1)",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if finished {
+            return Ok(None);
+        }
+        let position: usize = transaction.query_row(
+            "SELECT COUNT(*) FROM guesses WHERE session_id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        transaction.execute(
+            "INSERT INTO guesses (session_id, position, guess) VALUES (?1, ?2, ?3)",
+            params![id, position, guess],
+        )?;
+        let attempts = position + 1;
+        if let Some(result) = result(attempts) {
+            transaction.execute(
+                "INSERT INTO results (session_id, won, attempts) VALUES (?1, ?2, ?3)",
+                params![id, result.won, result.attempts],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(Some(attempts))
+    }
+
+    fn get_player_results(
+        &self,
+        player_id: &str,
+        length: Option<usize>,
+        with_archive: bool,
+    ) -> Result<Vec<(i64, GameResult)>, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        let results = connection
+            .prepare(
+                "SELECT s.day, r.won, r.attempts FROM results r
+                JOIN sessions s ON s.id = r.session_id
+                WHERE s.player_id = ?1 AND s.length = ?2 AND s.word IS NULL AND (?3 OR NOT s.archive)
+                ORDER BY s.day, r.rowid",
+            )?
+            .query_map(params![player_id, length_column(length), with_archive], |row| {
+                Ok((
+                    row.get(0)?,
+                    GameResult {
+                        won: row.get(1)?,
+                        attempts: row.get(2)?,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(results)
+    }
+
+    fn get_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+    ) -> Result<Option<String>, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        Ok(connection
+            .query_row(
+                "SELECT word FROM daily_words WHERE day = ?1 AND length = ?2",
+                params![day, length_column(length)],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn save_daily_word(
+        &self,
+        day: i64,
+        length: Option<usize>,
+        word: &str,
+    ) -> Result<(), StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        connection.execute(
+            "INSERT OR IGNORE INTO daily_words (day, length, word) VALUES (?1, ?2, ?3)",
+            params![day, length_column(length), word],
+        )?;
+        Ok(())
+    }
+
+    fn create_challenge(
+        &self,
+        id: &str,
+        word: &str,
+        day: i64,
+        client: &str,
+    ) -> Result<(), StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        connection.execute(
+            "INSERT INTO challenges (id, word, day, client) VALUES (?1, ?2, ?3, ?4)",
+            params![id, word, day, client],
+        )?;
+        Ok(())
+    }
+
+    fn count_challenges(&self, day: i64, client: Option<&str>) -> Result<usize, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        Ok(connection.query_row(
+            "SELECT COUNT(*) FROM challenges WHERE day = ?1 AND (?2 IS NULL OR client = ?2)",
+            params![day, client],
+            |row| row.get(0),
+        )?)
+    }
+
+    fn delete_challenges_before(&self, day: i64) -> Result<usize, StorageError> {
+        let mut connection = self.connection.lock().expect("Lock database");
+        let transaction = connection.transaction()?;
+        let sessions = "SELECT s.id FROM sessions s JOIN challenges c ON c.id = s.challenge_id WHERE c.day < ?1";
+        transaction.execute(
+            &format!("DELETE FROM guesses WHERE session_id IN ({sessions})"),
+            [day],
+        )?;
+        transaction.execute(
+            &format!("DELETE FROM results WHERE session_id IN ({sessions})"),
+            [day],
+        )?;
+        transaction.execute(
+            "DELETE FROM sessions WHERE challenge_id IN (SELECT id FROM challenges WHERE day < ?1)",
+            [day],
+        )?;
+        let deleted = transaction.execute("DELETE FROM challenges WHERE day < ?1", [day])?;
+        transaction.commit()?;
+        Ok(deleted)
+    }
+
+    fn get_challenge_word(&self, id: &str) -> Result<Option<String>, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        Ok(connection
+            .query_row("SELECT word FROM challenges WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })
+            .optional()?)
+    }
+
+    fn get_puzzle_words(&self) -> Result<Vec<String>, StorageError> {
+        let connection = self.connection.lock().expect("Lock database");
+        let words = connection
+            .prepare("SELECT word FROM daily_words UNION SELECT word FROM challenges")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_twice() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+
+        let version: usize = connection
+            .query_row("SELECT user_version FROM pragma_user_version", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_migrate_keeps_daily_words() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        for migration in &MIGRATIONS[..3] {
+            connection.execute_batch(migration).unwrap();
+        }
+        connection.pragma_update(None, "user_version", 3).unwrap();
+        connection
+            .execute(
+                "INSERT INTO daily_words (day, word) VALUES (42, 'ABAISSE')",
+                [],
+            )
+            .unwrap();
+
+        migrate(&mut connection).unwrap();
+        let storage = SqliteStorage {
+            connection: Mutex::new(connection),
+        };
+        assert_eq!(
+            storage.get_daily_word(42, None).unwrap(),
+            Some("ABAISSE".to_string())
+        );
+        assert_eq!(storage.get_daily_word(42, Some(7)).unwrap(), None);
+    }
+}
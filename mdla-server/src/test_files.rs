@@ -0,0 +1,47 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Number of the next file, so that tests run at the same time never share a file.
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+/// A file of a test in the temporary directory, deleted when it is dropped.
+#[derive(Debug)]
+pub struct TestFile {
+    path: PathBuf,
+}
+
+impl TestFile {
+    /// A file that does not exist yet, for the code tested to create it. The name ends with the given one,
+    /// to keep its extension.
+    pub fn new(name: &str) -> Self {
+        let n = NEXT_FILE.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("mdla-test-{}-{n}-{name}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Self { path }
+    }
+
+    /// A file with the given content.
+    pub fn with_content(name: &str, content: &str) -> Self {
+        let file = Self::new(name);
+        file.write(content);
+        file
+    }
+
+    pub fn write(&self, content: &str) {
+        fs::write(&self.path, content).unwrap();
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for TestFile {
+    fn drop(&mut self) {
+        // The test may have deleted it already
+        let _ = fs::remove_file(&self.path);
+    }
+}